@@ -8,37 +8,59 @@ use subtle::{ConstantTimeEq, ConstantTimeLess};
 use crate::derive_password_key;
 
 use crate::{
-    Config, Errors, KeyBuffer, RoundKeyBuffer, VERSION, calculate_hmac,
+    Config, Errors, KeyBuffer, RecoveryKey, VERSION, calculate_hmac,
+    cipher::header::Header,
     engine::{
         cache_warmup::{CacheWarmup, CacheWarmup64},
         engine::{
-            GaloisField, apply_gf, ctr_decrypt, ctr_encrypt, generate_dynamic_sbox,
-            generate_inv_s_box, in_s_bytes, inverse_shift_rows, rxa_decrypt, rxa_encrypt, s_bytes,
-            shift_rows,
+            GaloisField, ctr_decrypt, ctr_encrypt, generate_dynamic_sbox, generate_inv_s_box,
+            rxa_decrypt, rxa_encrypt,
         },
+        fused::{fused_decrypt, fused_encrypt},
     },
     generate_recovery_key, parse_recovery_key,
     rng_utils::{
-        nonce::{AsNonce, NonceData},
-        salt::{AsSalt, Salt},
+        nonce::{Nonce, NonceData, NonceType},
+        rng::RNG,
+        salt::Salt,
     },
     secure_zeroize,
-    utils::base_utils::AsBase,
+    utils::key_cache::KeyCache,
 };
 
-fn encrypt<'a>(
-    password: &[u8],
-    data: &'a [u8],
+/// Per-operation inputs of `encrypt`.
+struct EncryptParams<'a> {
     nonce: NonceData,
-    config: Config,
     custom_salt: Option<Salt>,
     wrap_all: bool,
     recovery_key: Option<bool>,
+    associated_data: &'a [u8],
+}
+
+fn encrypt<'a>(
+    password: &[u8],
+    data: &'a [u8],
+    config: Config,
+    params: EncryptParams<'_>,
     output_buffer: &mut Vec<u8>,
-) -> Result<(), Errors> {
+) -> Result<Option<RecoveryKey>, Errors> {
+    let EncryptParams {
+        nonce,
+        custom_salt,
+        wrap_all,
+        recovery_key,
+        associated_data,
+    } = params;
     let mut data = data.to_vec();
 
-    let estimated_size = data.len() + nonce.as_bytes().len() + VERSION.len() + 64 + 32;
+    let header = Header::from_config(
+        &config,
+        wrap_all.then_some(nonce),
+        custom_salt.filter(|_| wrap_all),
+    )?
+    .to_bytes();
+
+    let estimated_size = header.len() + data.len() + VERSION.len() + 64;
 
     output_buffer.clear();
     output_buffer.reserve(estimated_size);
@@ -71,7 +93,7 @@ fn encrypt<'a>(
 
     let mut buffer = [0u8; 64];
     let pwd: Vec<u8> = key.expose_secret().iter().take(64).cloned().collect();
-    let sbox = generate_dynamic_sbox(nonce, key.expose_secret(), config)?;
+    let sbox = generate_dynamic_sbox(nonce, key.expose_secret(), config, Some(KeyCache::global()))?;
     let inv_sbox = generate_inv_s_box(&sbox);
     buffer[..pwd.len()].copy_from_slice(&pwd);
     let pwd = CacheWarmup64::new(buffer, sbox, inv_sbox);
@@ -79,17 +101,13 @@ fn encrypt<'a>(
         pwd.warm_cache();
     }
 
-    if let Some(recovery_key) = recovery_key {
-        if recovery_key == true {
-            println!("Recovery Key: {}", generate_recovery_key(&pwd.key, nonce));
-        }
-    }
+    let recovery_key = recovery_key
+        .unwrap_or(false)
+        .then(|| generate_recovery_key(key.expose_secret(), nonce));
 
     let gf = Arc::new(GaloisField::new(config.gf_poly.value()));
 
-    if wrap_all {
-        output_buffer.extend_from_slice(nonce);
-    }
+    output_buffer.extend_from_slice(&header);
 
     {
         let mut version = VERSION.to_vec();
@@ -97,57 +115,7 @@ fn encrypt<'a>(
         output_buffer.extend_from_slice(&version);
     }
 
-    rxa_encrypt(&pwd, &mut data, config)?;
-
-    s_bytes(&mut data, &pwd, config)?;
-
-    rxa_encrypt(&pwd, &mut data, config)?;
-
-    apply_gf(&mut data, &config, &gf, nonce)?;
-
-    shift_rows(&mut data, &config);
-
-    s_bytes(&mut data, &pwd, config)?;
-
-    let mut round_key_buffer = RoundKeyBuffer::new(vec![0u8; 64]);
-
-    for i in 1..=config.rounds {
-        let slice_end = std::cmp::min(i * 32, 64);
-        let round_key = {
-            let mut hash = Sha3_512::new();
-            hash.update(&pwd.key[..slice_end]);
-            let result = hash.finalize();
-            round_key_buffer.0.copy_from_slice(&result);
-            &round_key_buffer.0
-        };
-
-        let mut buffer = [0u8; 64];
-        buffer.copy_from_slice(&round_key);
-        let round_key = &CacheWarmup64 {
-            key: buffer,
-            sbox: [0u8; 256],
-            inv_sbox: [0u8; 256],
-        };
-        if config.hardware.warmup_cache {
-            round_key.warm_cache();
-        }
-
-        data.chunks_mut(1024 * 1024)
-            .try_for_each(|chunk| -> Result<(), Errors> {
-                rxa_encrypt(&round_key, chunk, config)?;
-                match config.multi_round_galois_field {
-                    true => apply_gf(chunk, &config, &gf, nonce)?,
-                    false => {
-                        if i == 1 {
-                            apply_gf(chunk, &config, &gf, nonce)?;
-                        }
-                    }
-                }
-                Ok(())
-            })?;
-    }
-
-    drop(round_key_buffer);
+    fused_encrypt(&mut data, &pwd, &gf, nonce, &config)?;
 
     if config.ctr_layer && data.len() >= 128 {
         let mut iv = [0u8; 32];
@@ -160,15 +128,24 @@ fn encrypt<'a>(
     let mac_data_1 = hash_data.finalize().to_vec();
     let meta_data = vec![0xac, 0x07, 0x13, 0x00];
 
-    let total_len =
-        mac_data_1.len() + mac_data_1.len() + VERSION.len() + meta_data.len() + nonce.len();
+    let total_len = header.len()
+        + mac_data_1.len()
+        + mac_data_1.len()
+        + VERSION.len()
+        + meta_data.len()
+        + nonce.len()
+        + 8
+        + associated_data.len();
 
     let mut mac_data = Vec::with_capacity(total_len);
+    mac_data.extend_from_slice(&header);
     mac_data.extend_from_slice(&data_for_mac);
     mac_data.extend_from_slice(&mac_data_1);
     mac_data.extend_from_slice(VERSION);
     mac_data.extend_from_slice(&meta_data);
     mac_data.extend_from_slice(nonce);
+    mac_data.extend_from_slice(&(associated_data.len() as u64).to_le_bytes());
+    mac_data.extend_from_slice(associated_data);
     let mac = calculate_hmac(&pwd.key, &mac_data)?;
 
     drop(pwd);
@@ -178,80 +155,58 @@ fn encrypt<'a>(
 
     secure_zeroize(&mut data, &config);
 
-    if wrap_all {
-        if custom_salt.is_some() {
-            output_buffer.extend_from_slice(
-                custom_salt
-                    .ok_or(Errors::BuildFailed("Cannot Open Salt".to_string()))?
-                    .as_bytes(),
-            );
-        } else {
-            output_buffer.extend_from_slice(nonce);
-        }
-    }
-
-    Ok(())
+    Ok(recovery_key)
 }
 
 // -----------------------------------------------------
 
+/// Secret used to unlock a ciphertext.
+enum KeySource<'a> {
+    Password(&'a [u8]),
+    RecoveryKey(&'a str),
+}
+
 fn decrypt<'a>(
-    password: &[u8],
+    key_source: KeySource<'_>,
     data: &'a [u8],
     nonce: Option<NonceData>,
     config: Config,
     custom_salt: Option<Salt>,
-    wrap_all: bool,
-    recovery_key: Option<SecretBox<[u8]>>,
+    associated_data: &[u8],
     output_buffer: &mut Vec<u8>,
 ) -> Result<(), Errors> {
-    #[cfg(feature = "key_derivation")]
-    let (nonce_data, custom_salt) = if let Some(nonce) = nonce {
-        (nonce, custom_salt)
-    } else {
-        let (_, custom_salt) = data.split_at(data.len() - 32);
-        let (nonce, _) = data.split_at(32);
-
-        (nonce.as_nonce(), Option::from(custom_salt.as_salt()))
-    };
+    let (header, header_len) = Header::parse(data)?;
+    let config = header.apply(config);
 
-    #[cfg(not(feature = "key_derivation"))]
-    let (nonce_data, _) = if let Some(nonce) = nonce {
-        (nonce, custom_salt)
-    } else {
-        let (_, custom_salt) = data.split_at(data.len() - 32);
-        let (nonce, _) = data.split_at(32);
-
-        (nonce.as_nonce(), Option::from(custom_salt.as_salt()))
-    };
+    let nonce_data = header.nonce.or(nonce).ok_or_else(|| {
+        Errors::InvalidNonce("Nonce is not wrapped into the data, provide it".to_string())
+    })?;
+    let custom_salt = header.salt.or(custom_salt);
 
     output_buffer.clear();
 
     let nonce_byte = nonce_data.as_bytes();
 
-    #[cfg(feature = "key_derivation")]
-    let pwd = if config.key_derivation {
-        let pwd = derive_password_key(password, nonce_byte, custom_salt, config, 64)?;
-        pwd
-    } else {
-        password.to_vec()
-    };
-
-    #[cfg(not(feature = "key_derivation"))]
-    let pwd = password.to_vec();
-
-    let key = if let Some(key) = recovery_key {
-        KeyBuffer::new(parse_recovery_key(
-            &key.expose_secret().to_vec().as_string(),
-            nonce_byte,
-        )?)
-    } else {
-        KeyBuffer::new(pwd)
+    let key = match key_source {
+        #[cfg(feature = "key_derivation")]
+        KeySource::Password(password) if config.key_derivation => KeyBuffer::new(
+            derive_password_key(password, nonce_byte, custom_salt, config, 32)?,
+        ),
+        KeySource::Password(password) => {
+            let _ = custom_salt;
+            KeyBuffer::new(password.to_vec())
+        }
+        KeySource::RecoveryKey(phrase) => KeyBuffer::new(parse_recovery_key(phrase, nonce_byte)?),
     };
 
     let mut buffer = [0u8; 64];
     let pwd: Vec<u8> = key.expose_secret().iter().take(64).cloned().collect();
-    let sbox = generate_dynamic_sbox(nonce_byte, key.expose_secret(), config)?;
+    let sbox = generate_dynamic_sbox(
+        nonce_byte,
+        key.expose_secret(),
+        config,
+        Some(KeyCache::global()),
+    )?;
     let inv_sbox = generate_inv_s_box(&sbox);
     buffer[..pwd.len()].copy_from_slice(&pwd);
     let mut pwd = CacheWarmup64::new(buffer, sbox, inv_sbox);
@@ -259,24 +214,14 @@ fn decrypt<'a>(
         pwd.warm_cache();
     }
 
-    if data.len() < 32 + VERSION.len() {
-        return Err(Errors::InvalidMac("Data is too short".to_string()));
-    }
-
+    let (header_bytes, rest) = data.split_at(header_len);
     let version_len = VERSION.len();
-    let mut wrapped = false;
-
-    let (rest, encrypted_version) = if nonce.is_some() && !wrap_all {
-        let (encrypted_version, rest) = data.split_at(version_len);
 
-        (rest, encrypted_version)
-    } else {
-        let (_, rest) = data.split_at(32);
-        let (encrypted_version, rest) = rest.split_at(version_len);
+    if rest.len() < version_len + 64 {
+        return Err(Errors::InvalidMac("Data is too short".to_string()));
+    }
 
-        wrapped = true;
-        (rest, encrypted_version)
-    };
+    let (encrypted_version, rest) = rest.split_at(version_len);
 
     let mut encrypted_version = encrypted_version.to_vec();
     rxa_decrypt(&pwd, &mut encrypted_version, config)?;
@@ -291,15 +236,8 @@ fn decrypt<'a>(
         return Err(Errors::NotBackwardCompatible);
     }
 
-    let (mut crypted, mac_key) = if encrypted_version.starts_with(b"CRYSTALYST-version") && wrapped
-    {
-        let (data_without_salt, _) = rest.split_at(rest.len() - 32);
-        let (crypted, mac_key) = data_without_salt.split_at(data_without_salt.len() - 64);
-        (crypted.to_vec(), mac_key.to_vec())
-    } else {
-        let (crypted, mac_key) = rest.split_at(rest.len() - 64);
-        (crypted.to_vec(), mac_key.to_vec())
-    };
+    let (crypted, mac_key) = rest.split_at(rest.len() - 64);
+    let (mut crypted, mac_key) = (crypted.to_vec(), mac_key.to_vec());
 
     let mut hash_data = Sha3_512::new();
     hash_data.update(&crypted);
@@ -313,79 +251,31 @@ fn decrypt<'a>(
 
     let gf = Arc::new(GaloisField::new(config.gf_poly.value()));
 
-    let mut round_key_buffer = RoundKeyBuffer::new(vec![0u8; 64]);
-
-    for i in (1..=config.rounds).rev() {
-        let slice_end = std::cmp::min(i * 32, 64);
-
-        let round_key = {
-            let mut hash = Sha3_512::new();
-            hash.update(&pwd.key[..slice_end]);
-            let result = hash.finalize();
-            round_key_buffer.0.copy_from_slice(&result);
-            &round_key_buffer.0
-        };
-
-        let mut buffer = [0u8; 64];
-        buffer.copy_from_slice(&round_key);
-        let round_key = CacheWarmup64 {
-            key: buffer,
-            sbox: [0u8; 256],
-            inv_sbox: [0u8; 256],
-        };
-
-        if config.hardware.warmup_cache {
-            round_key.warm_cache();
-        }
-
-        crypted
-            .chunks_mut(1024 * 1024)
-            .try_for_each(|chunk| -> Result<(), Errors> {
-                match config.multi_round_galois_field {
-                    true => apply_gf(chunk, &config, &gf, nonce_byte)?,
-                    false => {
-                        if i == 1 {
-                            apply_gf(chunk, &config, &gf, nonce_byte)?;
-                        }
-                    }
-                }
-
-                rxa_decrypt(&round_key, chunk, config)?;
-                Ok(())
-            })?;
-    }
-
-    drop(round_key_buffer);
-
-    in_s_bytes(&mut crypted, &pwd, config)?;
-
-    inverse_shift_rows(&mut crypted, &config);
-
-    apply_gf(&mut crypted, &config, &gf, nonce_byte)?;
-
-    rxa_decrypt(&pwd, &mut crypted, config)?;
-
-    in_s_bytes(&mut crypted, &pwd, config)?;
-
-    rxa_decrypt(&pwd, &mut crypted, config)?;
+    fused_decrypt(&mut crypted, &pwd, &gf, nonce_byte, &config)?;
 
     let metdata = vec![0xac, 0x07, 0x13, 0x00];
     let mut hash_data = Sha3_512::new();
     hash_data.update(&crypted);
     let mac_data_2 = hash_data.finalize();
 
-    let total_len = mac_data_2.len()
+    let total_len = header_bytes.len()
+        + mac_data_2.len()
         + mac_data_1.len()
         + encrypted_version.len()
         + metdata.len()
-        + nonce_byte.len();
+        + nonce_byte.len()
+        + 8
+        + associated_data.len();
 
     let mut mac_data = Vec::with_capacity(total_len);
+    mac_data.extend_from_slice(header_bytes);
     mac_data.extend_from_slice(&mac_data_2);
     mac_data.extend_from_slice(&mac_data_1);
     mac_data.extend_from_slice(&encrypted_version);
     mac_data.extend_from_slice(&metdata);
     mac_data.extend_from_slice(nonce_byte);
+    mac_data.extend_from_slice(&(associated_data.len() as u64).to_le_bytes());
+    mac_data.extend_from_slice(associated_data);
     let mut mac = calculate_hmac(&pwd.key, &mac_data)?;
 
     if mac.ct_eq(&mac_key).unwrap_u8() != 1 {
@@ -399,6 +289,7 @@ fn decrypt<'a>(
 
     output_buffer.reserve(crypted.len());
     output_buffer.extend_from_slice(&crypted);
+    secure_zeroize(&mut crypted, &config);
 
     Ok(())
 }
@@ -445,7 +336,7 @@ pub struct CrystalystBuilder<'a> {
     password: Option<SecretBox<[u8]>>,
     nonce: Option<NonceData>,
     salt: Option<Salt>,
-    decryption_key: Option<SecretBox<[u8]>>,
+    associated_data: Option<&'a [u8]>,
     utils: Option<Utils>,
 }
 
@@ -458,7 +349,7 @@ impl<'a> CrystalystBuilder<'a> {
             config: None,
             nonce: None,
             salt: None,
-            decryption_key: None,
+            associated_data: None,
             utils: None,
         }
     }
@@ -494,11 +385,12 @@ impl<'a> CrystalystBuilder<'a> {
         self
     }
 
-    /// Sets the recovery decryption key for the decryption.
-    pub fn decrypt_from_recovery_key(mut self, decryption_key: String) -> Self {
-        self.decryption_key = Some(SecretBox::new(
-            decryption_key.as_bytes().to_vec().into_boxed_slice(),
-        ));
+    /// Sets the associated data (AAD) for the encryption & decryption.
+    /// - Associated data is authenticated but not encrypted, and it is not stored in the output.
+    /// - Decryption must be given the exact same associated data, otherwise MAC validation fails.
+    /// - Use it to bind a ciphertext to its context, such as a record id, file path or protocol header.
+    pub fn associated_data(mut self, associated_data: &'a [u8]) -> Self {
+        self.associated_data = Some(associated_data);
         self
     }
 
@@ -510,13 +402,16 @@ impl<'a> CrystalystBuilder<'a> {
     /// Encrypts the data using the provided configuration, password, and nonce.
     /// - Recommended using at the end of build.
     ///
+    /// # Returns
+    /// The recovery key if `Utils::recovery_key(true)` is set, otherwise `None`.
+    ///
     /// # Errors
     /// Returns an error if any of the required fields are missing.
     ///
     /// # Recommendations
     /// - Use a strong password.
     /// - Use a unique nonce for each encryption.
-    pub fn encrypt(self, output_buffer: &mut Vec<u8>) -> Result<(), Errors> {
+    pub fn encrypt(self, output_buffer: &mut Vec<u8>) -> Result<Option<RecoveryKey>, Errors> {
         let config = self
             .config
             .ok_or_else(|| Errors::BuildFailed("Missing Config".to_string()))?;
@@ -530,22 +425,27 @@ impl<'a> CrystalystBuilder<'a> {
             .nonce
             .ok_or_else(|| Errors::BuildFailed("Missing Nonce".to_string()))?;
         let salt = self.salt;
+        let associated_data = self.associated_data.unwrap_or_default();
         let (recovery_key, benchmark, wrap_all) = if let Some(utils) = self.utils {
             (utils.recovery_key, utils.benchmark, utils.wrap_all)
         } else {
             (None, false, false)
         };
+        let params = EncryptParams {
+            nonce,
+            custom_salt: salt,
+            wrap_all,
+            recovery_key,
+            associated_data,
+        };
 
         if benchmark {
             let start = Instant::now();
             let out = encrypt(
                 &password.expose_secret(),
                 &mut data,
-                nonce,
                 config,
-                salt,
-                wrap_all,
-                recovery_key,
+                params,
                 output_buffer,
             )?;
             let duration = start.elapsed();
@@ -555,19 +455,19 @@ impl<'a> CrystalystBuilder<'a> {
             encrypt(
                 &password.expose_secret(),
                 &mut data,
-                nonce,
                 config,
-                salt,
-                wrap_all,
-                recovery_key,
+                params,
                 output_buffer,
             )
         }
     }
 
-    /// Decrypts the data using the provided configuration, password, and nonce.
+    /// Decrypts the data using the provided password.
     /// - Recommended using at the end of build.
     /// - Recommended not using with encryption in same builder.
+    /// - Rounds, Galois field, CTR layer and key derivation settings are read from the ciphertext header,
+    ///   `config` is only needed for runtime options such as threads and zeroize (defaults to `Config::DEFAULT`).
+    /// - Nonce and salt are only needed when the data was encrypted without `wrap_all`.
     ///
     /// # Errors
     /// Returns an error if any of the required fields are missing.
@@ -575,34 +475,108 @@ impl<'a> CrystalystBuilder<'a> {
     /// # Recommendations
     /// - Renew the nonce after each decryption.
     pub fn decrypt(self, output_buffer: &mut Vec<u8>) -> Result<(), Errors> {
-        let config = self
-            .config
-            .ok_or_else(|| Errors::BuildFailed("Missing Config".to_string()))?;
-        let data = self
-            .data
-            .ok_or_else(|| Errors::BuildFailed("Missing Data".to_string()))?;
         let password = self
             .password
+            .as_ref()
             .ok_or_else(|| Errors::BuildFailed("Missing Password".to_string()))?;
+
+        self.run_decrypt(KeySource::Password(password.expose_secret()), output_buffer)
+    }
+
+    /// Decrypts the data using the recovery key returned by `encrypt`, no password is needed.
+    /// - Nonce and salt are only needed when the data was encrypted without `wrap_all`.
+    ///
+    /// # Errors
+    /// Returns `Errors::InvalidRecoveryKey` if the phrase is malformed,
+    /// and the same errors as a wrong password if it belongs to another ciphertext.
+    pub fn decrypt_with_recovery_key(
+        self,
+        recovery_key: &str,
+        output_buffer: &mut Vec<u8>,
+    ) -> Result<(), Errors> {
+        self.run_decrypt(KeySource::RecoveryKey(recovery_key), output_buffer)
+    }
+
+    /// Re-encrypts the data under a new password, the plaintext never leaves the library.
+    /// - The MAC is verified with `old_password` before anything is re-encrypted.
+    /// - A fresh nonce and salt are generated and always wrapped into the output, `nonce` and `salt`
+    ///   are only used to open the old data when it was encrypted without `wrap_all`.
+    /// - Rounds, Galois field, CTR layer and key derivation settings are carried over from the old header.
+    /// - The intermediate plaintext is zeroized according to `Config::zeroize`.
+    /// - Recovery keys of the old ciphertext stop working, set `Utils::recovery_key(true)` to get a new one.
+    ///
+    /// # Errors
+    /// Returns the same errors as `decrypt` for a wrong `old_password` or tampered data,
+    /// `output_buffer` is left empty in that case.
+    pub fn rekey(
+        self,
+        old_password: &[u8],
+        new_password: &[u8],
+        output_buffer: &mut Vec<u8>,
+    ) -> Result<Option<RecoveryKey>, Errors> {
+        let data = self
+            .data
+            .ok_or_else(|| Errors::BuildFailed("Missing Data".to_string()))?;
+        let (header, _) = Header::parse(data)?;
+        let config = header.apply(self.config.unwrap_or(Config::DEFAULT));
+        let associated_data = self.associated_data.unwrap_or_default();
+        let recovery_key = self.utils.and_then(|utils| utils.recovery_key);
+        output_buffer.clear();
+
+        let mut plaintext = Vec::new();
+        decrypt(
+            KeySource::Password(old_password),
+            data,
+            self.nonce,
+            config,
+            self.salt,
+            associated_data,
+            &mut plaintext,
+        )?;
+
+        let result =
+            Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).and_then(|nonce| {
+                let params = EncryptParams {
+                    nonce,
+                    custom_salt: Some(Salt::salt()),
+                    wrap_all: true,
+                    recovery_key,
+                    associated_data,
+                };
+                encrypt(new_password, &plaintext, config, params, output_buffer)
+            });
+
+        secure_zeroize(&mut plaintext, &config);
+        if result.is_err() {
+            output_buffer.clear();
+        }
+
+        result
+    }
+
+    fn run_decrypt(
+        &self,
+        key_source: KeySource<'_>,
+        output_buffer: &mut Vec<u8>,
+    ) -> Result<(), Errors> {
+        let config = self.config.unwrap_or(Config::DEFAULT);
+        let data = self
+            .data
+            .ok_or_else(|| Errors::BuildFailed("Missing Data".to_string()))?;
         let nonce = self.nonce;
         let salt = self.salt;
-        let recovery_key = self.decryption_key;
-        let (benchmark, wrap_all) = if let Some(utils) = self.utils {
-            (utils.benchmark, utils.wrap_all)
-        } else {
-            (false, false)
-        };
+        let associated_data = self.associated_data.unwrap_or_default();
+        let benchmark = self.utils.is_some_and(|utils| utils.benchmark);
 
         if benchmark {
             let start = Instant::now();
             let out = decrypt(
-                &password.expose_secret(),
-                &data,
+                key_source,
+                data,
                 nonce,
                 config,
                 salt,
-                wrap_all,
-                recovery_key,
+                associated_data,
                 output_buffer,
             );
             let duration = start.elapsed();
@@ -610,13 +584,12 @@ impl<'a> CrystalystBuilder<'a> {
             out
         } else {
             decrypt(
-                &password.expose_secret(),
-                &data,
+                key_source,
+                data,
                 nonce,
                 config,
                 salt,
-                wrap_all,
-                recovery_key,
+                associated_data,
                 output_buffer,
             )
         }
@@ -0,0 +1,510 @@
+//! # Ciphertext Header
+//!
+//! Every ciphertext produced by the block cipher starts with a small, unencrypted header that
+//! describes all decryption-relevant parameters of the `Config` that produced it.
+//! The header is covered by the MAC, so tampering with any field is detected during decryption.
+//!
+//! ## Layout (all integers little-endian)
+//! | Offset | Size | Field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 4    | Magic (`CRYS`)                          |
+//! | 4      | 1    | Header version                          |
+//! | 5      | 1    | Flags                                   |
+//! | 6      | 2    | Rounds                                  |
+//! | 8      | 1    | Galois field type                       |
+//! | 9      | 2    | Galois field polynomial                 |
+//! | 11     | 1    | Argon2 variant                          |
+//! | 12     | 4    | Argon2 memory cost (KiB)                |
+//! | 16     | 4    | Argon2 iterations                       |
+//! | 20     | 4    | Argon2 lanes                            |
+//! | 24     | 32   | Nonce (only if the nonce flag is set)   |
+//! | ..     | 32   | Salt (only if the salt flag is set)     |
+//!
+//! ## Stream Files
+//! Stream files (`.cryst`) start with a `StreamHeader` wrapping the header above.
+//! Nonce and salt are always present, so decryption only needs the password.
+//!
+//! | Offset | Size | Field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 4    | Magic (`CRST`)                          |
+//! | 4      | 1    | Stream header version                   |
+//! | 5      | 4    | Chunk size                              |
+//! | 9      | 2    | Length of the config header             |
+//! | 11     | ..   | Config header                           |
+//! | ..     | 8    | Config fingerprint                      |
+
+use std::io::{ErrorKind, Read};
+
+use sha3::{Digest, Sha3_256};
+
+#[cfg(feature = "key_derivation")]
+use crate::{Argon2Params, Argon2Type};
+use crate::{
+    Config, Errors, GaloisFieldType, IrreduciblePoly,
+    rng_utils::{
+        nonce::{AsNonce, NonceData},
+        salt::{AsSalt, Salt},
+    },
+};
+
+/// Magic bytes identifying a CRYSTALYST ciphertext.
+pub const MAGIC: [u8; 4] = *b"CRYS";
+/// Current header layout version.
+pub const HEADER_VERSION: u8 = 2;
+
+const FIXED_LEN: usize = 24;
+
+/// Magic bytes identifying a CRYSTALYST stream file.
+pub const STREAM_MAGIC: [u8; 4] = *b"CRST";
+/// Current stream header layout version.
+pub const STREAM_HEADER_VERSION: u8 = 1;
+/// Largest chunk size accepted in a stream header.
+pub const MAX_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Length of the stream header prefix that determines the full header length.
+pub const STREAM_PREFIX_LEN: usize = 11;
+const MAX_CONFIG_HEADER_LEN: usize = FIXED_LEN + 64;
+const FINGERPRINT_LEN: usize = 8;
+
+const FLAG_NONCE: u8 = 1 << 0;
+const FLAG_SALT: u8 = 1 << 1;
+const FLAG_KEY_DERIVATION: u8 = 1 << 2;
+const FLAG_MULTI_ROUND_GF: u8 = 1 << 3;
+const FLAG_CTR_LAYER: u8 = 1 << 4;
+const KNOWN_FLAGS: u8 =
+    FLAG_NONCE | FLAG_SALT | FLAG_KEY_DERIVATION | FLAG_MULTI_ROUND_GF | FLAG_CTR_LAYER;
+
+/// Decryption-relevant parameters stored in front of every ciphertext.
+#[derive(Debug, Clone, Copy)]
+pub struct Header {
+    pub rounds: u16,
+    pub gf_type: GaloisFieldType,
+    pub gf_poly: u16,
+    pub multi_round_galois_field: bool,
+    pub ctr_layer: bool,
+    pub key_derivation: bool,
+    #[cfg(feature = "key_derivation")]
+    pub argon2_type: Argon2Type,
+    #[cfg(feature = "key_derivation")]
+    pub argon2_params: Argon2Params,
+    /// Present when the ciphertext was produced with `wrap_all`.
+    pub nonce: Option<NonceData>,
+    /// Present when the ciphertext was produced with `wrap_all` and a custom salt.
+    pub salt: Option<Salt>,
+}
+
+impl Header {
+    /// Builds a header describing `config`.
+    ///
+    /// # Errors
+    /// Returns an error if the round count does not fit into the header.
+    pub fn from_config(
+        config: &Config,
+        nonce: Option<NonceData>,
+        salt: Option<Salt>,
+    ) -> Result<Self, Errors> {
+        let rounds = u16::try_from(config.rounds).map_err(|_| {
+            Errors::InvalidHeader(format!("Round count must be at most {}", u16::MAX))
+        })?;
+
+        #[cfg(feature = "key_derivation")]
+        let key_derivation = config.key_derivation;
+        #[cfg(not(feature = "key_derivation"))]
+        let key_derivation = false;
+
+        Ok(Self {
+            rounds,
+            gf_type: config.gf_type,
+            gf_poly: config.gf_poly.value(),
+            multi_round_galois_field: config.multi_round_galois_field,
+            ctr_layer: config.ctr_layer,
+            key_derivation,
+            #[cfg(feature = "key_derivation")]
+            argon2_type: config.argon2_type,
+            #[cfg(feature = "key_derivation")]
+            argon2_params: config.argon2_params,
+            nonce,
+            salt,
+        })
+    }
+
+    /// Returns `config` with every decryption-relevant field replaced by the header's value.
+    /// - Runtime options (threads, zeroize, hardware...) are kept from `config`.
+    pub fn apply(&self, mut config: Config) -> Config {
+        config.rounds = self.rounds as usize;
+        config.gf_type = self.gf_type;
+        config.gf_poly = match self.gf_poly {
+            0x11B => IrreduciblePoly::AES,
+            0x14D => IrreduciblePoly::Conway,
+            poly => IrreduciblePoly::Custom(poly),
+        };
+        config.multi_round_galois_field = self.multi_round_galois_field;
+        config.ctr_layer = self.ctr_layer;
+        #[cfg(feature = "key_derivation")]
+        {
+            config.key_derivation = self.key_derivation;
+            config.argon2_type = self.argon2_type;
+            config.argon2_params = self.argon2_params;
+        }
+        config
+    }
+
+    /// Serializes the header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut flags = 0u8;
+        if self.nonce.is_some() {
+            flags |= FLAG_NONCE;
+        }
+        if self.salt.is_some() {
+            flags |= FLAG_SALT;
+        }
+        if self.key_derivation {
+            flags |= FLAG_KEY_DERIVATION;
+        }
+        if self.multi_round_galois_field {
+            flags |= FLAG_MULTI_ROUND_GF;
+        }
+        if self.ctr_layer {
+            flags |= FLAG_CTR_LAYER;
+        }
+
+        #[cfg(feature = "key_derivation")]
+        let (argon2_type, argon2_params) = (
+            argon2_type_id(self.argon2_type),
+            [
+                self.argon2_params.memory_cost,
+                self.argon2_params.iterations,
+                self.argon2_params.lanes,
+            ],
+        );
+        #[cfg(not(feature = "key_derivation"))]
+        let (argon2_type, argon2_params) = (0u8, [0u32; 3]);
+
+        let mut out = Vec::with_capacity(FIXED_LEN + 64);
+        out.extend_from_slice(&MAGIC);
+        out.push(HEADER_VERSION);
+        out.push(flags);
+        out.extend_from_slice(&self.rounds.to_le_bytes());
+        out.push(gf_type_id(self.gf_type));
+        out.extend_from_slice(&self.gf_poly.to_le_bytes());
+        out.push(argon2_type);
+        for value in argon2_params {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+
+        if let Some(nonce) = self.nonce {
+            out.extend_from_slice(nonce.as_bytes());
+        }
+        if let Some(salt) = self.salt {
+            out.extend_from_slice(salt.as_bytes());
+        }
+
+        out
+    }
+
+    /// Parses a header from the start of `data`.
+    ///
+    /// # Returns
+    /// The header and the number of bytes it occupies.
+    pub fn parse(data: &[u8]) -> Result<(Self, usize), Errors> {
+        if data.len() < FIXED_LEN {
+            return Err(Errors::InvalidHeader("Data is too short".to_string()));
+        }
+
+        if data[..4] != MAGIC {
+            return Err(Errors::InvalidHeader(
+                "Missing CRYSTALYST header, data may be produced by an older version".to_string(),
+            ));
+        }
+
+        if data[4] != HEADER_VERSION {
+            return Err(Errors::InvalidHeader(format!(
+                "Unsupported header version: {}",
+                data[4]
+            )));
+        }
+
+        let flags = data[5];
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(Errors::InvalidHeader(format!(
+                "Unknown header flags: {:#04x}",
+                flags & !KNOWN_FLAGS
+            )));
+        }
+        let rounds = u16::from_le_bytes([data[6], data[7]]);
+        if rounds == 0 {
+            return Err(Errors::InvalidHeader("Round count cannot be 0".to_string()));
+        }
+        let gf_type = gf_type_from_id(data[8])?;
+        let gf_poly = u16::from_le_bytes([data[9], data[10]]);
+        let key_derivation = flags & FLAG_KEY_DERIVATION != 0;
+
+        #[cfg(feature = "key_derivation")]
+        let argon2_type = argon2_type_from_id(data[11])?;
+        #[cfg(feature = "key_derivation")]
+        let argon2_params = {
+            let read = |at: usize| {
+                u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+            };
+            let params = Argon2Params::new(read(12), read(16), read(20));
+            if params.memory_cost > Argon2Params::MAX_MEMORY_COST {
+                return Err(Errors::InvalidHeader(format!(
+                    "Argon2 memory cost too high: {} KiB",
+                    params.memory_cost
+                )));
+            }
+            if params.iterations > Argon2Params::MAX_ITERATIONS {
+                return Err(Errors::InvalidHeader(format!(
+                    "Argon2 iteration count too high: {}",
+                    params.iterations
+                )));
+            }
+            if params.lanes > Argon2Params::MAX_LANES {
+                return Err(Errors::InvalidHeader(format!(
+                    "Argon2 lane count too high: {}",
+                    params.lanes
+                )));
+            }
+            // Builds without `key_derivation` write zeros, they only matter if Argon2 runs.
+            if key_derivation && params.iterations == 0 {
+                return Err(Errors::InvalidHeader(
+                    "Argon2 iteration count cannot be 0".to_string(),
+                ));
+            }
+            if key_derivation && params.lanes == 0 {
+                return Err(Errors::InvalidHeader(
+                    "Argon2 lane count cannot be 0".to_string(),
+                ));
+            }
+            params
+        };
+        #[cfg(not(feature = "key_derivation"))]
+        if key_derivation {
+            return Err(Errors::InvalidHeader(
+                "Data requires the `key_derivation` feature".to_string(),
+            ));
+        }
+
+        let mut offset = FIXED_LEN;
+
+        let nonce = if flags & FLAG_NONCE != 0 {
+            let bytes = data
+                .get(offset..offset + 32)
+                .ok_or_else(|| Errors::InvalidHeader("Truncated nonce".to_string()))?;
+            offset += 32;
+            Some(bytes.as_nonce())
+        } else {
+            None
+        };
+
+        let salt = if flags & FLAG_SALT != 0 {
+            let bytes = data
+                .get(offset..offset + 32)
+                .ok_or_else(|| Errors::InvalidHeader("Truncated salt".to_string()))?;
+            offset += 32;
+            Some(bytes.as_salt())
+        } else {
+            None
+        };
+
+        Ok((
+            Self {
+                rounds,
+                gf_type,
+                gf_poly,
+                multi_round_galois_field: flags & FLAG_MULTI_ROUND_GF != 0,
+                ctr_layer: flags & FLAG_CTR_LAYER != 0,
+                key_derivation,
+                #[cfg(feature = "key_derivation")]
+                argon2_type,
+                #[cfg(feature = "key_derivation")]
+                argon2_params,
+                nonce,
+                salt,
+            },
+            offset,
+        ))
+    }
+}
+
+/// Header written in front of every stream file.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamHeader {
+    pub chunk_size: u32,
+    /// Config header, nonce and salt are always set.
+    pub header: Header,
+}
+
+impl StreamHeader {
+    /// Builds a stream header.
+    ///
+    /// # Errors
+    /// Returns an error if the chunk size is 0 or larger than `MAX_CHUNK_SIZE`,
+    /// or if `header` carries no nonce or no salt.
+    pub fn new(chunk_size: usize, header: Header) -> Result<Self, Errors> {
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(Errors::ChunkError(format!(
+                "Chunk size must be between 1 and {} bytes",
+                MAX_CHUNK_SIZE
+            )));
+        }
+
+        if header.nonce.is_none() || header.salt.is_none() {
+            return Err(Errors::InvalidHeader(
+                "Stream header requires a nonce and a salt".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            chunk_size: chunk_size as u32,
+            header,
+        })
+    }
+
+    /// Truncated SHA3-256 of the chunk size and every config field.
+    /// - Nonce and salt are not included, files encrypted with the same settings share a fingerprint.
+    pub fn fingerprint(&self) -> [u8; FINGERPRINT_LEN] {
+        let config = Header {
+            nonce: None,
+            salt: None,
+            ..self.header
+        };
+
+        let mut hash = Sha3_256::new();
+        hash.update(self.chunk_size.to_le_bytes());
+        hash.update(config.to_bytes());
+        let result = hash.finalize();
+
+        let mut fingerprint = [0u8; FINGERPRINT_LEN];
+        fingerprint.copy_from_slice(&result[..FINGERPRINT_LEN]);
+        fingerprint
+    }
+
+    /// Serializes the stream header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = self.header.to_bytes();
+
+        let mut out = Vec::with_capacity(STREAM_PREFIX_LEN + header.len() + FINGERPRINT_LEN);
+        out.extend_from_slice(&STREAM_MAGIC);
+        out.push(STREAM_HEADER_VERSION);
+        out.extend_from_slice(&self.chunk_size.to_le_bytes());
+        out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&self.fingerprint());
+
+        out
+    }
+
+    /// Returns the full length of a stream header from its first `STREAM_PREFIX_LEN` bytes.
+    pub fn encoded_len(prefix: &[u8]) -> Result<usize, Errors> {
+        if prefix.len() < STREAM_PREFIX_LEN {
+            return Err(Errors::InvalidHeader(
+                "Stream header is truncated".to_string(),
+            ));
+        }
+
+        if prefix[..4] != STREAM_MAGIC {
+            return Err(Errors::InvalidHeader(
+                "Missing CRYSTALYST stream header".to_string(),
+            ));
+        }
+
+        if prefix[4] != STREAM_HEADER_VERSION {
+            return Err(Errors::InvalidHeader(format!(
+                "Unsupported stream header version: {}",
+                prefix[4]
+            )));
+        }
+
+        let header_len = u16::from_le_bytes([prefix[9], prefix[10]]) as usize;
+        if header_len > MAX_CONFIG_HEADER_LEN {
+            return Err(Errors::InvalidHeader(format!(
+                "Config header too long: {} bytes",
+                header_len
+            )));
+        }
+
+        Ok(STREAM_PREFIX_LEN + header_len + FINGERPRINT_LEN)
+    }
+
+    /// Reads a stream header from the start of `reader`.
+    ///
+    /// # Returns
+    /// The header and its raw bytes, the bytes are bound into the chunk tags.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<(Self, Vec<u8>), Errors> {
+        let mut out = vec![0u8; STREAM_PREFIX_LEN];
+        read_exact(reader, &mut out)?;
+
+        out.resize(Self::encoded_len(&out)?, 0);
+        read_exact(reader, &mut out[STREAM_PREFIX_LEN..])?;
+
+        let chunk_size = u32::from_le_bytes([out[5], out[6], out[7], out[8]]) as usize;
+        let header_len = out.len() - STREAM_PREFIX_LEN - FINGERPRINT_LEN;
+
+        let header_end = STREAM_PREFIX_LEN + header_len;
+        let (header, consumed) = Header::parse(&out[STREAM_PREFIX_LEN..header_end])?;
+        if consumed != header_len {
+            return Err(Errors::InvalidHeader(
+                "Config header length mismatch".to_string(),
+            ));
+        }
+
+        let stream_header = Self::new(chunk_size, header)?;
+        if stream_header.fingerprint() != out[header_end..] {
+            return Err(Errors::InvalidHeader(
+                "Config fingerprint mismatch, header is corrupted".to_string(),
+            ));
+        }
+
+        Ok((stream_header, out))
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<(), Errors> {
+    reader.read_exact(buffer).map_err(|e| match e.kind() {
+        ErrorKind::UnexpectedEof => Errors::InvalidHeader("Stream header is truncated".to_string()),
+        _ => Errors::IoError(e.to_string()),
+    })
+}
+
+fn gf_type_id(gf_type: GaloisFieldType) -> u8 {
+    match gf_type {
+        GaloisFieldType::Triangular => 0,
+        GaloisFieldType::AES => 1,
+        GaloisFieldType::Hybrid => 2,
+    }
+}
+
+fn gf_type_from_id(id: u8) -> Result<GaloisFieldType, Errors> {
+    match id {
+        0 => Ok(GaloisFieldType::Triangular),
+        1 => Ok(GaloisFieldType::AES),
+        2 => Ok(GaloisFieldType::Hybrid),
+        _ => Err(Errors::InvalidHeader(format!(
+            "Unknown Galois field type: {}",
+            id
+        ))),
+    }
+}
+
+#[cfg(feature = "key_derivation")]
+fn argon2_type_id(argon2_type: Argon2Type) -> u8 {
+    match argon2_type {
+        Argon2Type::Argon2d => 0,
+        Argon2Type::Argon2i => 1,
+        Argon2Type::Argon2id => 2,
+    }
+}
+
+#[cfg(feature = "key_derivation")]
+fn argon2_type_from_id(id: u8) -> Result<Argon2Type, Errors> {
+    match id {
+        0 => Ok(Argon2Type::Argon2d),
+        1 => Ok(Argon2Type::Argon2i),
+        2 => Ok(Argon2Type::Argon2id),
+        _ => Err(Errors::InvalidHeader(format!(
+            "Unknown Argon2 type: {}",
+            id
+        ))),
+    }
+}
@@ -0,0 +1,208 @@
+//! # Kyber Public-Key Encryption
+//!
+//! `seal` encrypts a message to one or more Kyber public keys, `open` decrypts it with one of the
+//! matching private keys. A random content key encrypts the payload with the block cipher, and is
+//! wrapped for every recipient under a key derived from that recipient's Kyber shared secret.
+//!
+//! ## Layout (all integers little-endian)
+//! | Offset | Size | Field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 4    | Magic (`CRKM`)                          |
+//! | 4      | 1    | Envelope version                        |
+//! | 5      | 1    | Recipient count                         |
+//! | 6      | ..   | Recipient entries                       |
+//! | ..     | ..   | Payload ciphertext                      |
+//!
+//! Every recipient entry is the Kyber ciphertext, the wrapped key length (2) and the wrapped key.
+//!
+//! The envelope header is bound into the payload as associated data, so recipients cannot be
+//! added, removed or swapped. Recipients are anonymous, `open` tries every entry.
+
+use crate::{
+    Config, Errors, KeyBuffer,
+    cipher::block_cipher::{CrystalystBuilder, Utils},
+    kyber_wrap_key,
+    rng_utils::{
+        nonce::{Nonce, NonceType},
+        rng::RNG,
+    },
+    utils::kyber::{KyberCipherText, KyberPrivateKey, KyberPublicKey},
+    without_key_derivation,
+};
+
+/// Magic bytes identifying a sealed message.
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"CRKM";
+/// Current envelope layout version.
+pub const ENVELOPE_VERSION: u8 = 1;
+/// Maximum number of recipients of one message.
+pub const MAX_RECIPIENTS: usize = 255;
+
+const PREFIX_LEN: usize = 4 + 1 + 1;
+const CONTENT_KEY_LEN: usize = 64;
+
+/// Encrypts `plaintext` for every key in `recipients` into `output_buffer`.
+/// - Any one of the matching private keys can `open` the message.
+/// - `config` is used for the payload, key derivation is skipped since every key is random.
+///
+/// # Errors
+/// Returns `Errors::KyberError` if there are no or too many recipients, or a public key is invalid.
+pub fn seal(
+    recipients: &[&KyberPublicKey],
+    plaintext: &[u8],
+    config: Config,
+    output_buffer: &mut Vec<u8>,
+) -> Result<(), Errors> {
+    if recipients.is_empty() || recipients.len() > MAX_RECIPIENTS {
+        return Err(Errors::KyberError(format!(
+            "Recipient count must be between 1 and {}, got {}",
+            MAX_RECIPIENTS,
+            recipients.len()
+        )));
+    }
+
+    let config = without_key_derivation(config);
+    let content_key = KeyBuffer::new([RNG::osrng().to_vec(), RNG::osrng().to_vec()].concat());
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&ENVELOPE_MAGIC);
+    header.push(ENVELOPE_VERSION);
+    header.push(recipients.len() as u8);
+
+    for public in recipients {
+        let (shared_secret, ciphertext) = public.encapsulate()?;
+        let wrap_key = KeyBuffer::new(wrap_key(shared_secret.expose_secret(), &ciphertext)?);
+
+        let mut wrapped = Vec::new();
+        CrystalystBuilder::new()
+            .data(content_key.expose_secret())
+            .password(wrap_key.expose_secret())
+            .nonce(Nonce::generate_nonce(
+                Some(RNG::osrng()),
+                NonceType::Classic,
+            )?)
+            .config(config)
+            .associated_data(ciphertext.as_bytes())
+            .utils(Utils::new().wrap_all(true))
+            .encrypt(&mut wrapped)?;
+
+        header.extend_from_slice(ciphertext.as_bytes());
+        header.extend_from_slice(&(wrapped.len() as u16).to_le_bytes());
+        header.extend_from_slice(&wrapped);
+    }
+
+    let mut payload = Vec::new();
+    CrystalystBuilder::new()
+        .data(plaintext)
+        .password(content_key.expose_secret())
+        .nonce(Nonce::generate_nonce(
+            Some(RNG::osrng()),
+            NonceType::Classic,
+        )?)
+        .config(config)
+        .associated_data(&header)
+        .utils(Utils::new().wrap_all(true))
+        .encrypt(&mut payload)?;
+
+    output_buffer.clear();
+    output_buffer.reserve(header.len() + payload.len());
+    output_buffer.extend_from_slice(&header);
+    output_buffer.extend_from_slice(&payload);
+
+    Ok(())
+}
+
+/// Decrypts a message produced by `seal` into `output_buffer`.
+/// - `config` only supplies runtime options, every cipher setting is read from the message.
+///
+/// # Errors
+/// Returns `Errors::KyberError` if the message is not addressed to `private_key`,
+/// and `Errors::InvalidMac` if the payload was tampered with.
+pub fn open(
+    private_key: &KyberPrivateKey,
+    sealed: &[u8],
+    config: Config,
+    output_buffer: &mut Vec<u8>,
+) -> Result<(), Errors> {
+    if sealed.len() < PREFIX_LEN {
+        return Err(Errors::InvalidHeader(
+            "Sealed message is too short".to_string(),
+        ));
+    } else if sealed[..4] != ENVELOPE_MAGIC {
+        return Err(Errors::InvalidHeader(
+            "Missing sealed message magic".to_string(),
+        ));
+    } else if sealed[4] != ENVELOPE_VERSION {
+        return Err(Errors::InvalidHeader(format!(
+            "Unsupported envelope version: {}",
+            sealed[4]
+        )));
+    }
+
+    let count = sealed[5] as usize;
+    if count == 0 {
+        return Err(Errors::InvalidHeader(
+            "Sealed message has no recipients".to_string(),
+        ));
+    }
+
+    let mut entries = Vec::with_capacity(count);
+    let mut offset = PREFIX_LEN;
+    for _ in 0..count {
+        let entry_len = pqc_kyber::KYBER_CIPHERTEXTBYTES + 2;
+        let entry = sealed
+            .get(offset..offset + entry_len)
+            .ok_or_else(|| Errors::InvalidHeader("Truncated recipient entry".to_string()))?;
+        let (ciphertext, len) = entry.split_at(pqc_kyber::KYBER_CIPHERTEXTBYTES);
+        let wrapped_len = u16::from_le_bytes([len[0], len[1]]) as usize;
+        let wrapped = sealed
+            .get(offset + entry_len..offset + entry_len + wrapped_len)
+            .ok_or_else(|| Errors::InvalidHeader("Truncated recipient entry".to_string()))?;
+
+        entries.push((ciphertext, wrapped));
+        offset += entry_len + wrapped_len;
+    }
+
+    let (header, payload) = sealed.split_at(offset);
+    let content_key = entries
+        .into_iter()
+        .find_map(|(ciphertext, wrapped)| {
+            unwrap_content_key(private_key, ciphertext, wrapped, config)
+        })
+        .ok_or_else(|| {
+            Errors::KyberError("Sealed message is not addressed to this private key".to_string())
+        })?;
+
+    CrystalystBuilder::new()
+        .data(payload)
+        .password(content_key.expose_secret())
+        .config(config)
+        .associated_data(header)
+        .decrypt(output_buffer)
+}
+
+/// Derives the key wrapping the content key from a recipient's shared secret.
+fn wrap_key(shared_secret: &[u8], ciphertext: &KyberCipherText) -> Result<Vec<u8>, Errors> {
+    kyber_wrap_key(shared_secret, ciphertext, b"CRYSTALYST-kem-wrap")
+}
+
+fn unwrap_content_key(
+    private_key: &KyberPrivateKey,
+    ciphertext: &[u8],
+    wrapped: &[u8],
+    config: Config,
+) -> Option<KeyBuffer> {
+    let ciphertext = KyberCipherText::new(ciphertext.to_vec());
+    let shared_secret = private_key.decapsulate(&ciphertext).ok()?;
+    let wrap_key = KeyBuffer::new(wrap_key(shared_secret.expose_secret(), &ciphertext).ok()?);
+
+    let mut content_key = Vec::with_capacity(CONTENT_KEY_LEN);
+    let opened = CrystalystBuilder::new()
+        .data(wrapped)
+        .password(wrap_key.expose_secret())
+        .config(config)
+        .associated_data(ciphertext.as_bytes())
+        .decrypt(&mut content_key);
+    let content_key = KeyBuffer::new(content_key);
+
+    (opened.is_ok() && content_key.expose_secret().len() == CONTENT_KEY_LEN).then_some(content_key)
+}
@@ -0,0 +1,433 @@
+//! # Key Slot Containers
+//!
+//! A container encrypts its payload once with a random data key, and stores that key wrapped
+//! in several independent slots. Any slot unlocks the data key, so slots can be added or revoked
+//! without re-encrypting the payload.
+//!
+//! - Password slots run the password through Argon2 when `Config::key_derivation` is enabled.
+//! - Recovery slots are unlocked with a recovery phrase, generated when the slot is added.
+//! - Kyber slots are unlocked with the recipient's Kyber private key (`kyber` feature), the wrap key
+//!   is derived from the shared secret with HMAC over the KEM ciphertext and the slot.
+//!
+//! The payload and every slot are regular CRYSTALYST ciphertexts with `wrap_all`, bound to the
+//! container id and slot type through associated data.
+//!
+//! ## Layout (all integers little-endian)
+//! | Offset | Size | Field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 4    | Magic (`CRKS`)                          |
+//! | 4      | 1    | Container version                       |
+//! | 5      | 32   | Container id                            |
+//! | 37     | 1    | Slot count                              |
+//! | 38     | ..   | Slots, each `type (1) len (4) slot`     |
+//! | ..     | ..   | Payload ciphertext                      |
+//!
+//! ## Revocation
+//! Revoking a slot removes its wrapped copy of the data key. Anyone who unlocked the container
+//! before may still know the data key, re-encrypt into a new container to lock them out for good.
+
+use crate::{
+    Config, Errors, KeyBuffer, RecoveryKey,
+    cipher::block_cipher::{CrystalystBuilder, Utils},
+    rng_utils::{
+        nonce::{Nonce, NonceData, NonceType},
+        rng::RNG,
+    },
+    without_key_derivation,
+};
+
+#[cfg(feature = "pqc_kyber")]
+use crate::{
+    kyber_wrap_key,
+    utils::kyber::{KyberCipherText, KyberModule, KyberPublicKey},
+};
+
+/// Magic bytes identifying a key slot container.
+pub const CONTAINER_MAGIC: [u8; 4] = *b"CRKS";
+/// Current container layout version.
+pub const CONTAINER_VERSION: u8 = 1;
+/// Maximum number of slots in a container.
+pub const MAX_SLOTS: usize = 16;
+
+const ID_LEN: usize = 32;
+const PREFIX_LEN: usize = 4 + 1 + ID_LEN + 1;
+const DATA_KEY_LEN: usize = 64;
+
+/// Type of a key slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+    Password,
+    RecoveryKey,
+    Kyber,
+}
+
+impl SlotKind {
+    fn id(self) -> u8 {
+        match self {
+            SlotKind::Password => 1,
+            SlotKind::RecoveryKey => 2,
+            SlotKind::Kyber => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, Errors> {
+        match id {
+            1 => Ok(SlotKind::Password),
+            2 => Ok(SlotKind::RecoveryKey),
+            3 => Ok(SlotKind::Kyber),
+            id => Err(Errors::KeySlotError(format!("Unknown slot type: {}", id))),
+        }
+    }
+}
+
+/// Secret used to unlock a key slot container.
+pub enum SlotKey<'a> {
+    Password(&'a [u8]),
+    RecoveryKey(&'a str),
+    #[cfg(feature = "pqc_kyber")]
+    Kyber(&'a KyberModule),
+}
+
+struct KeySlot {
+    kind: SlotKind,
+    wrapped: Vec<u8>,
+}
+
+/// ### Key slot container
+/// - A random data key encrypts the payload, every slot holds its own wrapped copy of the key.
+/// - Every operation that changes slots needs a `SlotKey` for an existing slot.
+pub struct KeySlotContainer {
+    config: Config,
+    id: [u8; ID_LEN],
+    slots: Vec<KeySlot>,
+    payload: Vec<u8>,
+}
+
+impl KeySlotContainer {
+    /// Encrypts `data` into a new container with a single password slot.
+    /// - `config` is used for the payload and for the password slots added later.
+    pub fn new(config: Config, data: &[u8], password: &[u8]) -> Result<Self, Errors> {
+        let data_key = KeyBuffer::new([RNG::osrng().to_vec(), RNG::osrng().to_vec()].concat());
+        let mut container = Self {
+            config,
+            id: *RNG::osrng().as_bytes(),
+            slots: Vec::new(),
+            payload: Vec::new(),
+        };
+
+        CrystalystBuilder::new()
+            .data(data)
+            .password(data_key.expose_secret())
+            .nonce(fresh_nonce()?)
+            .config(without_key_derivation(config))
+            .associated_data(&container.payload_aad())
+            .utils(Utils::new().wrap_all(true))
+            .encrypt(&mut container.payload)?;
+
+        let (wrapped, _) = container.wrap(SlotKind::Password, password, &data_key, false)?;
+        container.push(SlotKind::Password, wrapped);
+
+        Ok(container)
+    }
+
+    /// Sets the configuration used for new password slots and runtime options.
+    /// - Containers loaded with `from_bytes` default to `Config::DEFAULT`.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns the type of every slot, in slot order.
+    pub fn slot_kinds(&self) -> Vec<SlotKind> {
+        self.slots.iter().map(|slot| slot.kind).collect()
+    }
+
+    /// Decrypts the payload into `output_buffer`.
+    ///
+    /// # Errors
+    /// Returns `Errors::KeySlotError` if no slot can be unlocked with `key`.
+    pub fn decrypt(&self, key: SlotKey<'_>, output_buffer: &mut Vec<u8>) -> Result<(), Errors> {
+        let data_key = self.unlock(key)?;
+
+        CrystalystBuilder::new()
+            .data(&self.payload)
+            .password(data_key.expose_secret())
+            .config(self.config)
+            .associated_data(&self.payload_aad())
+            .decrypt(output_buffer)
+    }
+
+    /// Adds a password slot, unlocked with `key`.
+    ///
+    /// # Returns
+    /// The index of the new slot.
+    pub fn add_password(&mut self, key: SlotKey<'_>, password: &[u8]) -> Result<usize, Errors> {
+        self.check_capacity()?;
+        let data_key = self.unlock(key)?;
+        let (wrapped, _) = self.wrap(SlotKind::Password, password, &data_key, false)?;
+
+        Ok(self.push(SlotKind::Password, wrapped))
+    }
+
+    /// Adds a recovery slot, unlocked with `key`.
+    ///
+    /// # Returns
+    /// The index of the new slot and the recovery phrase that unlocks it, store it offline.
+    pub fn add_recovery_key(&mut self, key: SlotKey<'_>) -> Result<(usize, RecoveryKey), Errors> {
+        self.check_capacity()?;
+        let data_key = self.unlock(key)?;
+        let secret = KeyBuffer::new(RNG::osrng().to_vec());
+        let (wrapped, recovery_key) = self.wrap(
+            SlotKind::RecoveryKey,
+            secret.expose_secret(),
+            &data_key,
+            true,
+        )?;
+        let recovery_key = recovery_key
+            .ok_or_else(|| Errors::KeySlotError("Recovery key was not generated".to_string()))?;
+
+        Ok((self.push(SlotKind::RecoveryKey, wrapped), recovery_key))
+    }
+
+    /// Adds a Kyber slot for the holder of `public`, unlocked with `key`.
+    ///
+    /// # Returns
+    /// The index of the new slot.
+    #[cfg(feature = "pqc_kyber")]
+    pub fn add_kyber(
+        &mut self,
+        key: SlotKey<'_>,
+        public: &KyberPublicKey,
+    ) -> Result<usize, Errors> {
+        self.check_capacity()?;
+        let data_key = self.unlock(key)?;
+        let (shared_secret, ciphertext) = public.encapsulate()?;
+        let wrap_key =
+            KeyBuffer::new(self.kyber_wrap_key(shared_secret.expose_secret(), &ciphertext)?);
+        let (wrapped, _) =
+            self.wrap(SlotKind::Kyber, wrap_key.expose_secret(), &data_key, false)?;
+
+        Ok(self.push(SlotKind::Kyber, [ciphertext.as_bytes(), &wrapped].concat()))
+    }
+
+    /// Removes slot `slot`, unlocked with `key`.
+    /// - `key` may unlock the slot that is being revoked.
+    /// - Indices of the following slots shift down by one.
+    ///
+    /// # Errors
+    /// Returns `Errors::KeySlotError` if the slot does not exist or is the last one.
+    pub fn revoke(&mut self, key: SlotKey<'_>, slot: usize) -> Result<(), Errors> {
+        if slot >= self.slots.len() {
+            return Err(Errors::KeySlotError(format!(
+                "Slot {} does not exist",
+                slot
+            )));
+        } else if self.slots.len() == 1 {
+            return Err(Errors::KeySlotError(
+                "Cannot revoke the last slot, the container would be lost".to_string(),
+            ));
+        }
+
+        self.unlock(key)?;
+        self.slots.remove(slot);
+
+        Ok(())
+    }
+
+    /// Serializes the container.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let slots_len: usize = self.slots.iter().map(|slot| 5 + slot.wrapped.len()).sum();
+        let mut out = Vec::with_capacity(PREFIX_LEN + slots_len + self.payload.len());
+        out.extend_from_slice(&CONTAINER_MAGIC);
+        out.push(CONTAINER_VERSION);
+        out.extend_from_slice(&self.id);
+        out.push(self.slots.len() as u8);
+        for slot in &self.slots {
+            out.push(slot.kind.id());
+            out.extend_from_slice(&(slot.wrapped.len() as u32).to_le_bytes());
+            out.extend_from_slice(&slot.wrapped);
+        }
+        out.extend_from_slice(&self.payload);
+
+        out
+    }
+
+    /// Parses a container produced by `to_bytes`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Errors> {
+        if data.len() < PREFIX_LEN {
+            return Err(Errors::KeySlotError("Container is too short".to_string()));
+        } else if data[..4] != CONTAINER_MAGIC {
+            return Err(Errors::KeySlotError(
+                "Missing key slot container magic".to_string(),
+            ));
+        } else if data[4] != CONTAINER_VERSION {
+            return Err(Errors::KeySlotError(format!(
+                "Unsupported container version: {}",
+                data[4]
+            )));
+        }
+
+        let mut id = [0u8; ID_LEN];
+        id.copy_from_slice(&data[5..5 + ID_LEN]);
+        let count = data[PREFIX_LEN - 1] as usize;
+        if count == 0 || count > MAX_SLOTS {
+            return Err(Errors::KeySlotError(format!(
+                "Slot count must be between 1 and {}, got {}",
+                MAX_SLOTS, count
+            )));
+        }
+
+        let mut rest = &data[PREFIX_LEN..];
+        let mut slots = Vec::with_capacity(count);
+        for _ in 0..count {
+            if rest.len() < 5 {
+                return Err(Errors::KeySlotError("Truncated slot".to_string()));
+            }
+            let kind = SlotKind::from_id(rest[0])?;
+            let len = u32::from_le_bytes([rest[1], rest[2], rest[3], rest[4]]) as usize;
+            let wrapped = rest
+                .get(5..5 + len)
+                .ok_or_else(|| Errors::KeySlotError("Truncated slot".to_string()))?;
+            slots.push(KeySlot {
+                kind,
+                wrapped: wrapped.to_vec(),
+            });
+            rest = &rest[5 + len..];
+        }
+
+        Ok(Self {
+            config: Config::DEFAULT,
+            id,
+            slots,
+            payload: rest.to_vec(),
+        })
+    }
+
+    fn payload_aad(&self) -> Vec<u8> {
+        [&CONTAINER_MAGIC[..], &[CONTAINER_VERSION], &self.id].concat()
+    }
+
+    fn slot_aad(&self, kind: SlotKind) -> Vec<u8> {
+        [&self.id[..], &[kind.id()]].concat()
+    }
+
+    /// Wrap key of a Kyber slot, bound to the KEM ciphertext, the container id and the slot type.
+    #[cfg(feature = "pqc_kyber")]
+    fn kyber_wrap_key(
+        &self,
+        shared_secret: &[u8],
+        ciphertext: &KyberCipherText,
+    ) -> Result<Vec<u8>, Errors> {
+        let context = [
+            &b"CRYSTALYST-keyslot-wrap"[..],
+            &self.slot_aad(SlotKind::Kyber),
+        ]
+        .concat();
+        kyber_wrap_key(shared_secret, ciphertext, &context)
+    }
+
+    fn check_capacity(&self) -> Result<(), Errors> {
+        if self.slots.len() >= MAX_SLOTS {
+            return Err(Errors::KeySlotError(format!(
+                "Container already has the maximum of {} slots",
+                MAX_SLOTS
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, kind: SlotKind, wrapped: Vec<u8>) -> usize {
+        self.slots.push(KeySlot { kind, wrapped });
+        self.slots.len() - 1
+    }
+
+    /// Wraps the data key with `secret`, Argon2 is only used for password slots.
+    fn wrap(
+        &self,
+        kind: SlotKind,
+        secret: &[u8],
+        data_key: &KeyBuffer,
+        recovery_key: bool,
+    ) -> Result<(Vec<u8>, Option<RecoveryKey>), Errors> {
+        let config = match kind {
+            SlotKind::Password => self.config,
+            SlotKind::RecoveryKey | SlotKind::Kyber => without_key_derivation(self.config),
+        };
+
+        let mut wrapped = Vec::new();
+        let recovery_key = CrystalystBuilder::new()
+            .data(data_key.expose_secret())
+            .password(secret)
+            .nonce(fresh_nonce()?)
+            .config(config)
+            .associated_data(&self.slot_aad(kind))
+            .utils(Utils::new().wrap_all(true).recovery_key(recovery_key))
+            .encrypt(&mut wrapped)?;
+
+        Ok((wrapped, recovery_key))
+    }
+
+    /// Tries every slot of the matching type and returns the data key of the first one that opens.
+    fn unlock(&self, key: SlotKey<'_>) -> Result<KeyBuffer, Errors> {
+        if let SlotKey::RecoveryKey(phrase) = key {
+            RecoveryKey::verify(phrase)?;
+        }
+
+        let kind = match key {
+            SlotKey::Password(_) => SlotKind::Password,
+            SlotKey::RecoveryKey(_) => SlotKind::RecoveryKey,
+            #[cfg(feature = "pqc_kyber")]
+            SlotKey::Kyber(_) => SlotKind::Kyber,
+        };
+        let aad = self.slot_aad(kind);
+
+        for slot in self.slots.iter().filter(|slot| slot.kind == kind) {
+            let builder = CrystalystBuilder::new()
+                .config(self.config)
+                .associated_data(&aad);
+            let mut data_key = Vec::with_capacity(DATA_KEY_LEN);
+
+            let opened = match key {
+                SlotKey::Password(password) => builder
+                    .data(&slot.wrapped)
+                    .password(password)
+                    .decrypt(&mut data_key),
+                SlotKey::RecoveryKey(phrase) => builder
+                    .data(&slot.wrapped)
+                    .decrypt_with_recovery_key(phrase, &mut data_key),
+                #[cfg(feature = "pqc_kyber")]
+                SlotKey::Kyber(module) => {
+                    let ciphertext_len = pqc_kyber::KYBER_CIPHERTEXTBYTES.min(slot.wrapped.len());
+                    let (ciphertext, wrapped) = slot.wrapped.split_at(ciphertext_len);
+                    let ciphertext = KyberCipherText::new(ciphertext.to_vec());
+                    module
+                        .decapsulate(&ciphertext)
+                        .and_then(|shared_secret| {
+                            self.kyber_wrap_key(shared_secret.expose_secret(), &ciphertext)
+                        })
+                        .and_then(|wrap_key| {
+                            let wrap_key = KeyBuffer::new(wrap_key);
+                            builder
+                                .data(wrapped)
+                                .password(wrap_key.expose_secret())
+                                .decrypt(&mut data_key)
+                        })
+                }
+            };
+
+            let data_key = KeyBuffer::new(data_key);
+            if opened.is_ok() && data_key.expose_secret().len() == DATA_KEY_LEN {
+                return Ok(data_key);
+            }
+        }
+
+        Err(Errors::KeySlotError(
+            "No slot could be unlocked with the provided key".to_string(),
+        ))
+    }
+}
+
+fn fresh_nonce() -> Result<NonceData, Errors> {
+    Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic)
+}
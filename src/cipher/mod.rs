@@ -1,2 +1,7 @@
 pub mod block_cipher;
+pub mod header;
+#[cfg(feature = "pqc_kyber")]
+pub mod kem;
+pub mod keyslot;
+pub mod reference;
 pub mod stream_cipher;
@@ -0,0 +1,635 @@
+//! # Reference Implementation
+//!
+//! Slow, single-threaded and scalar version of the block cipher pipeline, used to prove that every
+//! backend produces byte-identical ciphertext.
+//! - Every data layer (RXA, S-box, Galois field, ShiftRows, CTR) is written out byte by byte,
+//!   without SIMD, thread pools or multiplication tables.
+//! - Key derivation, S-box generation, header and MAC have a single implementation and are shared
+//!   with the block cipher, they do not depend on the backend.
+//! - `differential_check` runs `CrystalystBuilder` with AVX2 on and off under every `ThreadStrategy`
+//!   and compares each output with the reference, so data encrypted on an AVX2 server decrypts
+//!   on a machine without AVX2.
+//!
+//! ```no_run
+//! use crystalyst_rs::cipher::reference::{differential_check, differential_check_profiles};
+//! use crystalyst_rs::profiles;
+//!
+//! // Lengths around block, chunk and round-chunk boundaries.
+//! for len in [16, 127, 128, 4099, 1024 * 1024 + 5] {
+//!     let data: Vec<u8> = (0..len).map(|i| (i * 31) as u8).collect();
+//!     differential_check(profiles::FAST, &data).unwrap();
+//! }
+//!
+//! differential_check_profiles(&[0x5a; 4099]).unwrap();
+//! ```
+
+use sha3::{Digest, Sha3_512};
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "key_derivation")]
+use crate::derive_password_key;
+#[cfg(target_arch = "x86_64")]
+use crate::engine::simd::{avx2_inverse_shift_rows, avx2_shift_rows};
+
+use crate::{
+    Config, Errors, GaloisFieldType, KeyBuffer, ThreadStrategy, VERSION, calculate_hmac,
+    cipher::{block_cipher::CrystalystBuilder, header::Header},
+    engine::engine::{
+        RATIO, ROTATIONS, generate_dynamic_sbox, generate_inv_s_box, inverse_shift_rows_block,
+        shift_rows_block,
+    },
+    rng_utils::{
+        nonce::{Nonce, NonceData, NonceType},
+        rng::RNG,
+        salt::Salt,
+    },
+    without_key_derivation,
+};
+
+/// Rounds run over the data in chunks of this size, the Galois field counter restarts per chunk.
+const ROUND_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Source index of every byte of a 16-byte block after ShiftRows.
+const SHIFT_ROWS: [usize; 16] = [0, 5, 10, 15, 4, 9, 14, 3, 8, 13, 2, 7, 12, 1, 6, 11];
+
+/// Thread strategies compared by `differential_check`.
+pub const THREAD_STRATEGIES: [ThreadStrategy; 7] = [
+    ThreadStrategy::AutoThread,
+    ThreadStrategy::FullThread,
+    ThreadStrategy::LowThread,
+    ThreadStrategy::BulkOperations,
+    ThreadStrategy::Gaming,
+    ThreadStrategy::SingleThread,
+    ThreadStrategy::Custom(3),
+];
+
+/// Key material of one operation, zeroized on drop.
+struct Keys {
+    key: [u8; 64],
+    sbox: [u8; 256],
+    inv_sbox: [u8; 256],
+}
+
+impl Keys {
+    fn new(key: &[u8], nonce: &[u8], config: Config) -> Result<Self, Errors> {
+        let mut padded = [0u8; 64];
+        let len = key.len().min(64);
+        padded[..len].copy_from_slice(&key[..len]);
+
+        let sbox = generate_dynamic_sbox(nonce, key, config, None)?;
+        let inv_sbox = generate_inv_s_box(&sbox);
+
+        Ok(Self {
+            key: padded,
+            sbox,
+            inv_sbox,
+        })
+    }
+
+    fn round_key(&self, round: usize) -> [u8; 64] {
+        let mut hash = Sha3_512::new();
+        hash.update(&self.key[..(round * 32).min(64)]);
+        hash.finalize().into()
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        self.key.zeroize();
+        self.sbox.zeroize();
+        self.inv_sbox.zeroize();
+    }
+}
+
+fn derive_key(
+    password: &[u8],
+    nonce: &[u8],
+    salt: Option<Salt>,
+    config: Config,
+) -> Result<KeyBuffer, Errors> {
+    #[cfg(feature = "key_derivation")]
+    if config.key_derivation {
+        return Ok(KeyBuffer::new(derive_password_key(
+            password, nonce, salt, config, 32,
+        )?));
+    }
+
+    let _ = (nonce, salt, config);
+    Ok(KeyBuffer::new(password.to_vec()))
+}
+
+fn rxa_forward(key: &[u8; 64], data: &mut [u8]) {
+    for (i, b) in data.iter_mut().enumerate() {
+        let k = key[i % 64];
+        *b = (b.rotate_left(ROTATIONS[i % 8]) ^ k).wrapping_add(k);
+    }
+}
+
+fn rxa_inverse(key: &[u8; 64], data: &mut [u8]) {
+    for (i, b) in data.iter_mut().enumerate() {
+        let k = key[i % 64];
+        *b = (b.wrapping_sub(k) ^ k).rotate_right(ROTATIONS[i % 8]);
+    }
+}
+
+fn substitute(table: &[u8; 256], data: &mut [u8]) {
+    for b in data.iter_mut() {
+        *b = table[*b as usize];
+    }
+}
+
+fn shift_rows(data: &mut [u8]) {
+    for block in data.chunks_exact_mut(16) {
+        let mut source = [0u8; 16];
+        source.copy_from_slice(block);
+        for (i, b) in block.iter_mut().enumerate() {
+            *b = source[SHIFT_ROWS[i]];
+        }
+    }
+}
+
+fn inverse_shift_rows(data: &mut [u8]) {
+    for block in data.chunks_exact_mut(16) {
+        let mut source = [0u8; 16];
+        source.copy_from_slice(block);
+        for (i, b) in source.iter().enumerate() {
+            block[SHIFT_ROWS[i]] = *b;
+        }
+    }
+}
+
+/// Multiplies in GF(2^8), only the low byte of the polynomial takes part.
+fn gf_mul(mut a: u8, mut b: u8, poly: u16) -> u8 {
+    let mut product = 0u8;
+
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+
+        let high_bit = a & 0x80;
+        a <<= 1;
+        if high_bit != 0 {
+            a ^= poly as u8;
+        }
+
+        b >>= 1;
+    }
+
+    product
+}
+
+/// Counter bytes of the `idx`-th Galois field column.
+fn gf_counter(nonce: &[u8], idx: usize) -> [u8; 8] {
+    let block_counter = (idx / 512) as u64 + 1;
+
+    (nonce[idx % nonce.len()] as u64)
+        .wrapping_add(block_counter)
+        .wrapping_add(idx as u64)
+        .wrapping_mul(RATIO)
+        .to_le_bytes()
+}
+
+fn aes_mix_columns(data: &mut [u8], nonce: &[u8], poly: u16) {
+    let mul = |a, b| gf_mul(a, b, poly);
+
+    for (idx, column) in data.chunks_exact_mut(4).enumerate() {
+        let c = gf_counter(nonce, idx);
+        let [s0, s1, s2, s3] = [c[0] ^ c[3], c[1] ^ c[4], c[2] ^ c[5], c[3] ^ c[6]];
+        let stream = [
+            mul(2, s0) ^ mul(3, s1) ^ s2 ^ s3,
+            s0 ^ mul(2, s1) ^ mul(3, s2) ^ s3,
+            s0 ^ s1 ^ mul(2, s2) ^ mul(3, s3),
+            mul(3, s0) ^ s1 ^ s2 ^ mul(2, s3),
+        ];
+
+        for (b, s) in column.iter_mut().zip(stream) {
+            *b ^= s;
+        }
+    }
+}
+
+fn triangle_mix_columns(data: &mut [u8], nonce: &[u8], poly: u16) {
+    let mul = |a, b| gf_mul(a, b, poly);
+
+    for (idx, column) in data.chunks_exact_mut(3).enumerate() {
+        let c = gf_counter(nonce, idx);
+        let [a, b, c] = [c[0] ^ c[3], c[1] ^ c[4], c[2] ^ c[5]];
+        let stream = [mul(3, a) ^ mul(4, b) ^ c, mul(4, b) ^ c, mul(6, c)];
+
+        for (byte, s) in column.iter_mut().zip(stream) {
+            *byte ^= s;
+        }
+    }
+}
+
+/// Galois field layer, XORs a data-independent stream so it is its own inverse.
+fn apply_gf(data: &mut [u8], config: &Config, nonce: &[u8]) -> Result<(), Errors> {
+    if data.is_empty() {
+        return Err(Errors::GaloisFieldError("Empty Data".to_string()));
+    }
+
+    let poly = config.gf_poly.value();
+    match config.gf_type {
+        GaloisFieldType::Triangular => triangle_mix_columns(data, nonce, poly),
+        GaloisFieldType::AES => aes_mix_columns(data, nonce, poly),
+        GaloisFieldType::Hybrid => {
+            aes_mix_columns(data, nonce, poly);
+            triangle_mix_columns(data, nonce, poly);
+        }
+    }
+
+    Ok(())
+}
+
+/// CTR layer, every 64-byte block masks its first 32 bytes with the previous ciphertext block.
+fn ctr_layer(nonce: &[u8], data: &mut [u8], encrypt: bool) {
+    let mut iv = [0u8; 32];
+    iv.copy_from_slice(&nonce[..32]);
+
+    for (idx, block) in data.chunks_exact_mut(64).enumerate() {
+        let block_counter = (idx / 32) as u64 + 1;
+        let stream: [u8; 8] = std::array::from_fn(|j| {
+            (nonce[j] as u64)
+                .wrapping_add(block_counter)
+                .wrapping_add(idx as u64)
+                .wrapping_add(j as u64)
+                .wrapping_mul(RATIO) as u8
+        });
+
+        let mut ciphertext = [0u8; 32];
+        if !encrypt {
+            ciphertext.copy_from_slice(&block[..32]);
+        }
+
+        for (i, b) in block[..32].iter_mut().enumerate() {
+            *b ^= iv[i].wrapping_mul(stream[i % 8]);
+        }
+
+        if encrypt {
+            ciphertext.copy_from_slice(&block[..32]);
+        }
+        iv = ciphertext;
+    }
+}
+
+/// Inputs of the MAC, in the order they are authenticated.
+struct MacInput<'a> {
+    header: &'a [u8],
+    plaintext: &'a [u8],
+    ciphertext: &'a [u8],
+    version: &'a [u8],
+    nonce: &'a [u8],
+    associated_data: &'a [u8],
+}
+
+fn mac(key: &[u8; 64], input: MacInput<'_>) -> Result<Vec<u8>, Errors> {
+    let mut mac_data = Vec::new();
+    mac_data.extend_from_slice(input.header);
+    mac_data.extend_from_slice(&Sha3_512::digest(input.plaintext));
+    mac_data.extend_from_slice(&Sha3_512::digest(input.ciphertext));
+    mac_data.extend_from_slice(input.version);
+    mac_data.extend_from_slice(&[0xac, 0x07, 0x13, 0x00]);
+    mac_data.extend_from_slice(input.nonce);
+    mac_data.extend_from_slice(&(input.associated_data.len() as u64).to_le_bytes());
+    mac_data.extend_from_slice(input.associated_data);
+
+    calculate_hmac(key, &mac_data)
+}
+
+/// Encrypts `data` like `CrystalystBuilder::encrypt` without `wrap_all`, salt or recovery key.
+/// - Thread strategy and AVX2 settings are ignored, the output is what every backend must produce.
+///
+/// # Errors
+/// Returns the same errors as the block cipher for short passwords or empty data.
+pub fn reference_encrypt(
+    password: &[u8],
+    data: &[u8],
+    nonce: NonceData,
+    config: Config,
+    associated_data: &[u8],
+    output_buffer: &mut Vec<u8>,
+) -> Result<(), Errors> {
+    let header = Header::from_config(&config, None, None)?.to_bytes();
+
+    if password.is_empty() {
+        return Err(Errors::EmptyPassword);
+    } else if password.len() < 32 {
+        return Err(Errors::PasswordTooShort(format!(
+            "Password must be at least {} characters for cryptographic strength.",
+            32
+        )));
+    }
+
+    let nonce = nonce.as_bytes();
+    let key = derive_key(password, nonce, None, config)?;
+    let keys = Keys::new(key.expose_secret(), nonce, config)?;
+
+    let mut version = VERSION.to_vec();
+    rxa_forward(&keys.key, &mut version);
+
+    let mut out = data.to_vec();
+    rxa_forward(&keys.key, &mut out);
+    substitute(&keys.sbox, &mut out);
+    rxa_forward(&keys.key, &mut out);
+    apply_gf(&mut out, &config, nonce)?;
+    shift_rows(&mut out);
+    substitute(&keys.sbox, &mut out);
+
+    for round in 1..=config.rounds {
+        let round_key = keys.round_key(round);
+
+        for chunk in out.chunks_mut(ROUND_CHUNK_SIZE) {
+            rxa_forward(&round_key, chunk);
+            if config.multi_round_galois_field || round == 1 {
+                apply_gf(chunk, &config, nonce)?;
+            }
+        }
+    }
+
+    if config.ctr_layer && out.len() >= 128 {
+        ctr_layer(nonce, &mut out, true);
+    }
+
+    let tag = mac(
+        &keys.key,
+        MacInput {
+            header: &header,
+            plaintext: data,
+            ciphertext: &out,
+            version: VERSION,
+            nonce,
+            associated_data,
+        },
+    )?;
+
+    output_buffer.clear();
+    output_buffer.extend_from_slice(&header);
+    output_buffer.extend_from_slice(&version);
+    output_buffer.extend_from_slice(&out);
+    output_buffer.extend_from_slice(&tag);
+
+    Ok(())
+}
+
+/// Decrypts `data` like `CrystalystBuilder::decrypt`.
+/// - Settings stored in the header override `config`, a wrapped nonce or salt is used if present.
+///
+/// # Errors
+/// Returns `Errors::InvalidMac` for a wrong password or tampered data, `output_buffer` is left empty.
+pub fn reference_decrypt(
+    password: &[u8],
+    data: &[u8],
+    nonce: NonceData,
+    config: Config,
+    associated_data: &[u8],
+    output_buffer: &mut Vec<u8>,
+) -> Result<(), Errors> {
+    output_buffer.clear();
+
+    let (header, header_len) = Header::parse(data)?;
+    let config = header.apply(config);
+    let nonce = header.nonce.unwrap_or(nonce);
+    let nonce = nonce.as_bytes();
+
+    let key = derive_key(password, nonce, header.salt, config)?;
+    let keys = Keys::new(key.expose_secret(), nonce, config)?;
+
+    let (header_bytes, rest) = data.split_at(header_len);
+    if rest.len() < VERSION.len() + 64 {
+        return Err(Errors::InvalidMac("Data is too short".to_string()));
+    }
+
+    let (version, rest) = rest.split_at(VERSION.len());
+    let mut version = version.to_vec();
+    rxa_inverse(&keys.key, &mut version);
+
+    if !version.starts_with(b"CRYSTALYST-version") {
+        return Err(Errors::InvalidAlgorithm);
+    }
+    if version.starts_with(b"CRYSTALYST-version:0x8") {
+        return Err(Errors::NotBackwardCompatible);
+    }
+
+    let (ciphertext, tag) = rest.split_at(rest.len() - 64);
+    let mut out = ciphertext.to_vec();
+
+    if config.ctr_layer && out.len() >= 128 {
+        ctr_layer(nonce, &mut out, false);
+    }
+
+    for round in (1..=config.rounds).rev() {
+        let round_key = keys.round_key(round);
+
+        for chunk in out.chunks_mut(ROUND_CHUNK_SIZE) {
+            if config.multi_round_galois_field || round == 1 {
+                apply_gf(chunk, &config, nonce)?;
+            }
+            rxa_inverse(&round_key, chunk);
+        }
+    }
+
+    substitute(&keys.inv_sbox, &mut out);
+    inverse_shift_rows(&mut out);
+    apply_gf(&mut out, &config, nonce)?;
+    rxa_inverse(&keys.key, &mut out);
+    substitute(&keys.inv_sbox, &mut out);
+    rxa_inverse(&keys.key, &mut out);
+
+    let expected = mac(
+        &keys.key,
+        MacInput {
+            header: header_bytes,
+            plaintext: &out,
+            ciphertext,
+            version: &version,
+            nonce,
+            associated_data,
+        },
+    )?;
+    if expected.ct_eq(tag).unwrap_u8() != 1 {
+        return Err(Errors::InvalidMac("Invalid authentication".to_string()));
+    }
+
+    output_buffer.extend_from_slice(&out);
+    Ok(())
+}
+
+/// Offset of the first differing byte, or the shorter length.
+fn first_difference(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .unwrap_or(a.len().min(b.len()))
+}
+
+/// Checks that every backend encrypts `data` to the reference ciphertext and decrypts it again.
+/// - Runs AVX2 on and off (AVX2 falls back to scalar code where unsupported) under every entry of
+///   `THREAD_STRATEGIES`, with a fresh random nonce and password.
+/// - Empty `data` is rejected by the cipher itself, so it is skipped.
+///
+/// # Errors
+/// Returns `Errors::BackendMismatch` naming the first backend whose output differs,
+/// or the error of a failing encryption or decryption.
+pub fn differential_check(config: Config, data: &[u8]) -> Result<(), Errors> {
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic)?;
+    let password = RNG::osrng().to_vec();
+    let associated_data = b"CRYSTALYST-differential-check";
+
+    let mut expected = Vec::new();
+    reference_encrypt(
+        &password,
+        data,
+        nonce,
+        config,
+        associated_data,
+        &mut expected,
+    )?;
+
+    let mut plaintext = Vec::new();
+    reference_decrypt(
+        &password,
+        &expected,
+        nonce,
+        config,
+        associated_data,
+        &mut plaintext,
+    )?;
+    if plaintext != data {
+        return Err(Errors::BackendMismatch(format!(
+            "Reference decryption differs at byte {}",
+            first_difference(&plaintext, data)
+        )));
+    }
+
+    for avx2 in [false, true] {
+        for strategy in THREAD_STRATEGIES {
+            let backend = config
+                .set_hardware(config.hardware.set_enable_avx2(avx2))
+                .set_thread(strategy);
+            let name = format!("{:?} with AVX2 {}", strategy, avx2);
+
+            let mut ciphertext = Vec::new();
+            CrystalystBuilder::new()
+                .data(data)
+                .password(&password)
+                .nonce(nonce)
+                .associated_data(associated_data)
+                .config(backend)
+                .encrypt(&mut ciphertext)?;
+            if ciphertext != expected {
+                return Err(Errors::BackendMismatch(format!(
+                    "{} encrypts differently at byte {}",
+                    name,
+                    first_difference(&ciphertext, &expected)
+                )));
+            }
+
+            plaintext.clear();
+            CrystalystBuilder::new()
+                .data(&expected)
+                .password(&password)
+                .nonce(nonce)
+                .associated_data(associated_data)
+                .config(backend)
+                .decrypt(&mut plaintext)
+                .map_err(|e| {
+                    Errors::BackendMismatch(format!("{} fails to decrypt: {}", name, e))
+                })?;
+            if plaintext != data {
+                return Err(Errors::BackendMismatch(format!(
+                    "{} decrypts differently at byte {}",
+                    name,
+                    first_difference(&plaintext, data)
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// In-place ShiftRows variant of the engine.
+type ShiftRows = fn(&mut [u8]);
+
+/// Checks the engine's scalar and AVX2 ShiftRows against the reference, in both directions.
+/// - Only whole 16-byte blocks are shuffled, a trailing partial block must be left untouched.
+/// - The AVX2 variant is skipped on CPUs without AVX2.
+///
+/// # Errors
+/// Returns `Errors::BackendMismatch` naming the first variant whose output differs.
+pub fn differential_check_shift_rows(data: &[u8]) -> Result<(), Errors> {
+    let scalar: (&str, ShiftRows, ShiftRows) = (
+        "Scalar",
+        |data| data.chunks_exact_mut(16).for_each(shift_rows_block),
+        |data| data.chunks_exact_mut(16).for_each(inverse_shift_rows_block),
+    );
+    #[cfg(target_arch = "x86_64")]
+    let avx2: Option<(&str, ShiftRows, ShiftRows)> = is_x86_feature_detected!("avx2").then_some((
+        "AVX2",
+        |data| unsafe { avx2_shift_rows(data) },
+        |data| unsafe { avx2_inverse_shift_rows(data) },
+    ));
+    #[cfg(not(target_arch = "x86_64"))]
+    let avx2 = None;
+
+    let mut expected = data.to_vec();
+    shift_rows(&mut expected);
+
+    for (name, forward, inverse) in std::iter::once(scalar).chain(avx2) {
+        let mut out = data.to_vec();
+        forward(&mut out);
+        if out != expected {
+            return Err(Errors::BackendMismatch(format!(
+                "{} ShiftRows of {} bytes differs at byte {}",
+                name,
+                data.len(),
+                first_difference(&out, &expected)
+            )));
+        }
+
+        inverse(&mut out);
+        if out != data {
+            return Err(Errors::BackendMismatch(format!(
+                "{} inverse ShiftRows of {} bytes differs at byte {}",
+                name,
+                data.len(),
+                first_difference(&out, data)
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `differential_check` for every profile, plus the triangular and hybrid Galois fields.
+/// - Key derivation is disabled, Argon2 runs before and independently of the backend.
+pub fn differential_check_profiles(data: &[u8]) -> Result<(), Errors> {
+    let profiles = [
+        Config::DEFAULT,
+        Config::CT_DEFAULT,
+        Config::FAST,
+        Config::BALANCED,
+        Config::SECURE,
+        Config::CT_SECURE,
+        Config::MAX,
+        Config::FORTRESS,
+        Config::EXTREME,
+        Config::REALTIME,
+        Config::CT_REALTIME,
+        Config::TRIANGLE_TEST_SUITE,
+        Config::DEFAULT.gf_type(GaloisFieldType::Hybrid),
+    ];
+
+    for config in profiles {
+        differential_check(without_key_derivation(config), data)?;
+    }
+
+    Ok(())
+}
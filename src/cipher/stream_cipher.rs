@@ -1,26 +1,47 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
-    io::{BufReader, BufWriter, Read, Write},
-    sync::Arc,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    sync::{Arc, Mutex, mpsc},
     time::Instant,
 };
 
-use sha3::{Digest, Sha3_512};
+use rayon::prelude::*;
 use subtle::{ConstantTimeEq, ConstantTimeLess};
 
+#[cfg(feature = "key_derivation")]
+use crate::derive_password_key;
 use crate::{
-    Config, Errors, KeyBuffer, NonceData, RoundKeyBuffer,
+    Config, Errors, KeyBuffer, NonceData, calculate_hmac,
+    cipher::header::{Header, StreamHeader},
     engine::{
         cache_warmup::{CacheWarmup, CacheWarmup64},
         engine::{
-            GaloisField, apply_gf, ctr_decrypt, ctr_encrypt, generate_dynamic_sbox,
-            generate_inv_s_box, in_s_bytes, inverse_shift_rows, rxa_decrypt, rxa_encrypt, s_bytes,
-            shift_rows,
+            GaloisField, ctr_decrypt, ctr_encrypt, generate_dynamic_sbox, generate_inv_s_box,
+            thread_pool,
         },
+        fused::{fused_decrypt, fused_encrypt},
     },
+    rng_utils::{
+        nonce::{AsNonce, Nonce, NonceType},
+        rng::RNG,
+        salt::Salt,
+    },
+    secure_zeroize,
 };
 
+#[cfg(feature = "async")]
+mod async_io;
+#[cfg(feature = "async")]
+pub use async_io::{AsyncCrystalystReader, AsyncCrystalystWriter};
+#[cfg(feature = "pqc_kyber")]
+mod channel;
+#[cfg(feature = "pqc_kyber")]
+pub use channel::{CHANNEL_MAGIC, CHANNEL_VERSION, CrystalystChannel, MAX_RECORD_SIZE};
+
 pub const CHUNK_SIZE: usize = 1024 * 1024;
+/// Size of the authentication tag appended to every chunk by the file functions.
+pub const TAG_SIZE: usize = 64;
 
 fn keystream(key: &[u8], nonce: &[u8], counter: u64) -> KeyBuffer {
     let key = key
@@ -40,6 +61,101 @@ fn keystream(key: &[u8], nonce: &[u8], counter: u64) -> KeyBuffer {
     KeyBuffer::new(key)
 }
 
+/// Derives the key of chunk number `counter` for the authenticated formats.
+/// - Bound to the full 64-bit counter, equal chunks at any distance encrypt differently.
+fn chunk_key(key: &[u8], nonce: &[u8], counter: u64) -> Result<KeyBuffer, Errors> {
+    let mut info = b"CRYSTALYST-stream-chunk".to_vec();
+    info.extend_from_slice(nonce);
+    info.extend_from_slice(&counter.to_le_bytes());
+    Ok(KeyBuffer::new(calculate_hmac(key, &info)?))
+}
+
+fn chunk_mac_key(key: &[u8], nonce: &[u8], header: &[u8]) -> Result<KeyBuffer, Errors> {
+    let mut info = b"CRYSTALYST-stream-mac".to_vec();
+    info.extend_from_slice(nonce);
+    info.extend_from_slice(header);
+    Ok(KeyBuffer::new(calculate_hmac(key, &info)?))
+}
+
+fn chunk_tag(
+    mac_key: &[u8],
+    counter: u64,
+    is_final: bool,
+    chunk: &[u8],
+) -> Result<Vec<u8>, Errors> {
+    let mut mac_data = Vec::with_capacity(17 + chunk.len());
+    mac_data.extend_from_slice(&counter.to_le_bytes());
+    mac_data.push(is_final as u8);
+    mac_data.extend_from_slice(&(chunk.len() as u64).to_le_bytes());
+    mac_data.extend_from_slice(chunk);
+    calculate_hmac(mac_key, &mac_data)
+}
+
+/// Reads until `buffer` is full or the reader is exhausted.
+fn read_chunk<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<usize, Errors> {
+    let mut filled = 0;
+
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(Errors::IoError(e.to_string())),
+        }
+    }
+
+    Ok(filled)
+}
+
+/// Keys of an unlocked stream file.
+struct StreamKeys {
+    cipher: ChunkCipher,
+    key: KeyBuffer,
+    mac_key: KeyBuffer,
+}
+
+impl StreamKeys {
+    /// Encrypts chunk number `counter` in place and returns its tag.
+    fn encrypt_chunk(
+        &self,
+        chunk: &mut [u8],
+        counter: u64,
+        is_final: bool,
+    ) -> Result<Vec<u8>, Errors> {
+        if !chunk.is_empty() {
+            let key = chunk_key(self.key.expose_secret(), &self.cipher.nonce, counter)?;
+            self.cipher.process_chunk(chunk, key.expose_secret())?;
+        }
+
+        chunk_tag(self.mac_key.expose_secret(), counter, is_final, chunk)
+    }
+
+    /// Verifies `tag` and decrypts chunk number `counter` in place.
+    fn decrypt_chunk(
+        &self,
+        chunk: &mut [u8],
+        tag: &[u8],
+        counter: u64,
+        is_final: bool,
+    ) -> Result<(), Errors> {
+        let expected = chunk_tag(self.mac_key.expose_secret(), counter, is_final, chunk)?;
+        if expected.ct_eq(tag).unwrap_u8() != 1 {
+            return Err(Errors::InvalidMac(format!(
+                "Chunk {} failed authentication",
+                counter
+            )));
+        }
+
+        if !chunk.is_empty() {
+            let key = chunk_key(self.key.expose_secret(), &self.cipher.nonce, counter)?;
+            self.cipher
+                .process_decrypt_chunk(chunk, key.expose_secret())?;
+        }
+
+        Ok(())
+    }
+}
+
 pub struct CrystalystStreamUtils {
     pub benchmark: bool,
 }
@@ -55,20 +171,36 @@ impl CrystalystStreamUtils {
 pub struct CrystalystStream {
     config: Config,
     pwd: KeyBuffer,
+    nonce: Option<[u8; 32]>,
+    chunk_size: usize,
+    chunks_in_flight: Option<usize>,
+    utils: CrystalystStreamUtils,
+}
+
+/// Per-file chunk transform, built from the config and nonce the data is encrypted with.
+struct ChunkCipher {
+    config: Config,
     nonce: [u8; 32],
     gf: Arc<GaloisField>,
-    utils: CrystalystStreamUtils,
 }
 
-impl CrystalystStream {
-    fn process_chunk(&self, chunk: &mut [u8], key: &[u8], key_len: usize) -> Result<(), Errors> {
+impl ChunkCipher {
+    fn new(config: Config, nonce: [u8; 32]) -> Self {
+        Self {
+            config,
+            nonce,
+            gf: Arc::new(GaloisField::new(config.gf_poly.value())),
+        }
+    }
+
+    fn process_chunk(&self, chunk: &mut [u8], key: &[u8]) -> Result<(), Errors> {
         let config = self.config;
         let nonce = self.nonce;
         let gf = &self.gf;
 
         let mut buffer = [0u8; 64];
         let pwd: Vec<u8> = key.iter().take(64).cloned().collect();
-        let sbox = generate_dynamic_sbox(&nonce, key, config)?;
+        let sbox = generate_dynamic_sbox(&nonce, key, config, None)?;
         let inv_sbox = generate_inv_s_box(&sbox);
         buffer[..pwd.len()].copy_from_slice(&pwd);
         let pwd = CacheWarmup64::new(buffer, sbox, inv_sbox);
@@ -76,58 +208,7 @@ impl CrystalystStream {
             pwd.warm_cache();
         }
 
-        rxa_encrypt(&pwd, chunk, config)?;
-
-        s_bytes(chunk, &pwd, config)?;
-
-        rxa_encrypt(&pwd, chunk, config)?;
-
-        apply_gf(chunk, &config, &gf, &nonce)?;
-
-        shift_rows(chunk, &config);
-
-        s_bytes(chunk, &pwd, config)?;
-
-        let mut round_key_buffer = RoundKeyBuffer::new(vec![0u8; key_len * 2]);
-
-        for i in 1..=config.rounds {
-            let slice_end = std::cmp::min(i * 32, 64);
-            let round_key = {
-                let mut hash = Sha3_512::new();
-                hash.update(&pwd.key[..slice_end]);
-                let result = hash.finalize();
-                round_key_buffer.0.copy_from_slice(&result);
-                &round_key_buffer.0
-            };
-
-            let mut buffer = [0u8; 64];
-            buffer.copy_from_slice(&round_key);
-            let round_key = &CacheWarmup64 {
-                key: buffer,
-                sbox: [0u8; 256],
-                inv_sbox: [0u8; 256],
-            };
-            if config.hardware.warmup_cache {
-                round_key.warm_cache();
-            }
-
-            chunk
-                .chunks_mut(CHUNK_SIZE)
-                .try_for_each(|chunk| -> Result<(), Errors> {
-                    rxa_encrypt(round_key, chunk, config)?;
-                    match config.multi_round_galois_field {
-                        true => apply_gf(chunk, &config, &gf, &nonce)?,
-                        false => {
-                            if i == 1 {
-                                apply_gf(chunk, &config, &gf, &nonce)?;
-                            }
-                        }
-                    }
-                    Ok(())
-                })?;
-        }
-
-        drop(round_key_buffer);
+        fused_encrypt(chunk, &pwd, gf, &nonce, &config)?;
 
         if config.ctr_layer && chunk.len() >= 128 {
             let mut iv = [0u8; 32];
@@ -138,19 +219,14 @@ impl CrystalystStream {
         Ok(())
     }
 
-    fn process_decrypt_chunk(
-        &self,
-        chunk: &mut [u8],
-        key: &[u8],
-        key_len: usize,
-    ) -> Result<(), Errors> {
+    fn process_decrypt_chunk(&self, chunk: &mut [u8], key: &[u8]) -> Result<(), Errors> {
         let config = self.config;
         let nonce = self.nonce;
         let gf = &self.gf;
 
         let mut buffer = [0u8; 64];
         let pwd: Vec<u8> = key.iter().take(64).cloned().collect();
-        let sbox = generate_dynamic_sbox(&nonce, key, config)?;
+        let sbox = generate_dynamic_sbox(&nonce, key, config, None)?;
         let inv_sbox = generate_inv_s_box(&sbox);
         buffer[..pwd.len()].copy_from_slice(&pwd);
         let pwd = CacheWarmup64::new(buffer, sbox, inv_sbox);
@@ -164,110 +240,251 @@ impl CrystalystStream {
             ctr_decrypt(&nonce, chunk, &iv);
         }
 
-        let mut round_key_buffer = RoundKeyBuffer::new(vec![0u8; key_len as usize * 2]);
-
-        for i in (1..=config.rounds).rev() {
-            let slice_end = std::cmp::min(i * 32, 64);
-
-            let round_key = {
-                let mut hash = Sha3_512::new();
-                hash.update(&pwd.key[..slice_end]);
-                let result = hash.finalize();
-                round_key_buffer.0.copy_from_slice(&result);
-                &round_key_buffer.0
-            };
-
-            let mut buffer = [0u8; 64];
-            buffer.copy_from_slice(&round_key);
-            let round_key = &CacheWarmup64 {
-                key: buffer,
-                sbox: [0u8; 256],
-                inv_sbox: [0u8; 256],
-            };
-            if config.hardware.warmup_cache {
-                round_key.warm_cache();
-            }
+        fused_decrypt(chunk, &pwd, gf, &nonce, &config)?;
 
-            chunk
-                .chunks_mut(CHUNK_SIZE)
-                .try_for_each(|chunk| -> Result<(), Errors> {
-                    match config.multi_round_galois_field {
-                        true => apply_gf(chunk, &config, &gf, &nonce)?,
-                        false => {
-                            if i == 1 {
-                                apply_gf(chunk, &config, &gf, &nonce)?;
-                            }
-                        }
-                    }
+        Ok(())
+    }
+}
 
-                    rxa_decrypt(round_key, chunk, config)?;
-                    Ok(())
-                })?;
+impl CrystalystStream {
+    /// Creates a stream for encryption and decryption.
+    pub fn new(config: Config, pwd: &[u8], nonce: NonceData) -> Self {
+        Self {
+            config,
+            pwd: KeyBuffer::new(pwd.to_vec()),
+            nonce: Some(*nonce.as_bytes()),
+            chunk_size: CHUNK_SIZE,
+            chunks_in_flight: None,
+            utils: CrystalystStreamUtils::new(false),
+        }
+    }
+
+    /// Creates a stream for decrypting stream files with only a password.
+    /// - Nonce, salt and every decryption-relevant setting are read from the file header.
+    /// - `config` only supplies runtime options (threads, zeroize, hardware...).
+    /// - Encryption requires a nonce, use `new` for it.
+    pub fn open(config: Config, pwd: &[u8]) -> Self {
+        Self {
+            config,
+            pwd: KeyBuffer::new(pwd.to_vec()),
+            nonce: None,
+            chunk_size: CHUNK_SIZE,
+            chunks_in_flight: None,
+            utils: CrystalystStreamUtils::new(false),
         }
+    }
 
-        drop(round_key_buffer);
+    pub fn utils(mut self, utils: CrystalystStreamUtils) -> Self {
+        self.utils = utils;
+        self
+    }
 
-        in_s_bytes(chunk, &pwd, config)?;
+    /// Sets the chunk size used for encryption, defaults to `CHUNK_SIZE`.
+    /// - Stream files store their chunk size, decryption always uses the stored value.
+    /// - Must be between 1 byte and `MAX_CHUNK_SIZE`.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
 
-        inverse_shift_rows(chunk, &config);
+    /// Sets how many chunks the file functions keep in memory at once, defaults to twice the worker count.
+    /// - Workers follow `Config::thread_strategy`, peak memory is about `chunks_in_flight * chunk_size`.
+    pub fn chunks_in_flight(mut self, chunks: usize) -> Self {
+        self.chunks_in_flight = Some(chunks.max(1));
+        self
+    }
 
-        apply_gf(chunk, &config, &gf, &nonce)?;
+    fn workers(&self) -> usize {
+        let workers = self.config.thread_strategy.get_cpu_count().max(1);
+        match self.chunks_in_flight {
+            Some(chunks) => workers.min(chunks),
+            None => workers,
+        }
+    }
 
-        rxa_decrypt(&pwd, chunk, config)?;
+    fn nonce(&self) -> Result<[u8; 32], Errors> {
+        self.nonce.ok_or_else(|| {
+            Errors::InvalidNonce("Stream was opened for decryption, provide a nonce".to_string())
+        })
+    }
 
-        in_s_bytes(chunk, &pwd, config)?;
+    /// Derives the chunk and MAC keys for a stream file, via Argon2 if key derivation is enabled.
+    fn stream_keys(
+        &self,
+        stream_header: &StreamHeader,
+        header: &[u8],
+    ) -> Result<StreamKeys, Errors> {
+        let config = stream_header.header.apply(self.config);
+        let (nonce, salt) = match (stream_header.header.nonce, stream_header.header.salt) {
+            (Some(nonce), Some(salt)) => (*nonce.as_bytes(), salt),
+            _ => {
+                return Err(Errors::InvalidHeader(
+                    "Stream header requires a nonce and a salt".to_string(),
+                ));
+            }
+        };
+
+        #[cfg(feature = "key_derivation")]
+        let key = if config.key_derivation {
+            KeyBuffer::new(derive_password_key(
+                self.pwd.expose_secret(),
+                &nonce,
+                Some(salt),
+                config,
+                32,
+            )?)
+        } else {
+            KeyBuffer::new(self.pwd.expose_secret().to_vec())
+        };
+
+        #[cfg(not(feature = "key_derivation"))]
+        let key = {
+            let _ = salt;
+            KeyBuffer::new(self.pwd.expose_secret().to_vec())
+        };
+
+        Ok(StreamKeys {
+            mac_key: chunk_mac_key(key.expose_secret(), &nonce, header)?,
+            key,
+            cipher: ChunkCipher::new(config, nonce),
+        })
+    }
 
-        rxa_decrypt(&pwd, chunk, config)?;
+    /// Creates a writer that encrypts everything written to it into `inner` as a `.cryst` stream.
+    /// - The stream header is written immediately, chunks are written as soon as they are full.
+    /// - Memory use is bounded by one chunk.
+    /// - `CrystalystWriter::finish` must be called, otherwise the stream is truncated and fails to decrypt.
+    pub fn writer<W: Write>(&self, mut inner: W) -> Result<CrystalystWriter<W>, Errors> {
+        self.check_password(32)?;
+
+        let nonce = self.nonce()?;
+        let header = Header::from_config(&self.config, Some(nonce.as_nonce()), Some(Salt::salt()))?;
+        let stream_header = StreamHeader::new(self.chunk_size, header)?;
+        let header = stream_header.to_bytes();
+        let keys = self.stream_keys(&stream_header, &header)?;
+
+        inner
+            .write_all(&header)
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+
+        Ok(CrystalystWriter {
+            inner,
+            keys,
+            chunk_size: self.chunk_size,
+            buffer: Vec::with_capacity(self.chunk_size + TAG_SIZE),
+            counter: 1,
+            poisoned: false,
+        })
+    }
 
-        Ok(())
+    /// Creates a reader that decrypts a `.cryst` stream from `inner`.
+    /// - The header is read and the key derived immediately.
+    /// - Memory use is bounded by one chunk, every chunk is authenticated before it is returned.
+    pub fn reader<R: Read>(&self, mut inner: R) -> Result<CrystalystReader<R>, Errors> {
+        self.check_password(32)?;
+
+        let (stream_header, header) = StreamHeader::read_from(&mut inner)?;
+        let keys = self.stream_keys(&stream_header, &header)?;
+
+        Ok(CrystalystReader {
+            inner,
+            keys,
+            frame_size: stream_header.chunk_size as usize + TAG_SIZE,
+            buffer: Vec::new(),
+            pos: 0,
+            counter: 1,
+            done: false,
+        })
     }
 
-    pub fn new(config: Config, pwd: &[u8], nonce: NonceData) -> Self {
-        Self {
-            config,
-            pwd: KeyBuffer::new(pwd.to_vec()),
-            nonce: *nonce.as_bytes(),
-            gf: Arc::new(GaloisField::new(config.gf_poly.value())),
-            utils: CrystalystStreamUtils::new(false),
+    /// Opens a `.cryst` stream for random access, starting at the reader's current position.
+    /// - Runs the key derivation once, reads are then served chunk by chunk.
+    /// - Only chunks that are read get authenticated, truncation is detected when the last chunk is read.
+    pub fn seekable_reader<R: Read + Seek>(
+        &self,
+        mut reader: R,
+    ) -> Result<CrystalystSeekReader<R>, Errors> {
+        self.check_password(32)?;
+
+        let (stream_header, header) = StreamHeader::read_from(&mut reader)?;
+        let keys = self.stream_keys(&stream_header, &header)?;
+
+        let data_start = reader
+            .stream_position()
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+        let end = reader
+            .seek(SeekFrom::End(0))
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+
+        let chunk_size = stream_header.chunk_size as u64;
+        let frame_size = chunk_size + TAG_SIZE as u64;
+        let total = end.saturating_sub(data_start);
+        let (full_chunks, rest) = (total / frame_size, total % frame_size);
+
+        if rest < TAG_SIZE as u64 {
+            return Err(Errors::InvalidMac(
+                "Stream truncated: final chunk is missing".to_string(),
+            ));
         }
+
+        Ok(CrystalystSeekReader {
+            inner: reader,
+            keys,
+            data_start,
+            chunk_size,
+            chunk_count: full_chunks + 1,
+            len: full_chunks * chunk_size + rest - TAG_SIZE as u64,
+            pos: 0,
+            chunk: Vec::new(),
+            chunk_index: None,
+        })
     }
 
-    pub fn utils(mut self, utils: CrystalystStreamUtils) -> Self {
-        self.utils = utils;
-        self
+    /// Decrypts `len` plaintext bytes starting at `offset` from a `.cryst` stream.
+    /// - Only the chunks covering the range are read, authenticated and decrypted.
+    /// - The result is shorter than `len` if the range extends past the end of the stream.
+    pub fn decrypt_range<R: Read + Seek>(
+        &self,
+        file: R,
+        offset: u64,
+        len: usize,
+    ) -> Result<Vec<u8>, Errors> {
+        let mut reader = self.seekable_reader(file)?;
+        reader.pos = offset;
+
+        let len = (len as u64).min(reader.len.saturating_sub(offset)) as usize;
+        let mut out = vec![0u8; len];
+        let mut filled = 0;
+
+        while filled < len {
+            filled += reader.read_plain(&mut out[filled..])?;
+        }
+
+        Ok(out)
     }
 
+    /// Encrypts `raw_data` in place.
+    /// - No authentication tags are produced, use the file functions when integrity matters.
     pub fn stream_encrypt(&mut self, raw_data: &mut [u8]) -> Result<(), Errors> {
         let start = Instant::now();
 
         let key_len = 32;
+        self.check_password(key_len)?;
 
-        if (self.pwd.expose_secret().len() as u64)
-            .ct_ne(&0)
-            .unwrap_u8()
-            != 1
-        {
-            return Err(Errors::EmptyPassword);
-        } else if (self.pwd.expose_secret().len() as u32)
-            .ct_lt(&key_len)
-            .unwrap_u8()
-            == 1
-        {
-            return Err(Errors::PasswordTooShort(format!(
-                "Password must be at least {} characters for cryptographic strength.",
-                key_len
-            )));
-        }
-
+        let cipher = ChunkCipher::new(self.config, self.nonce()?);
         let pwd = self.pwd.expose_secret().to_vec();
 
-        for (i, chunk) in raw_data.chunks_mut(CHUNK_SIZE).enumerate() {
-            let counter = i + 1;
-            let pwd = keystream(&pwd, &self.nonce, counter as u64);
+        thread_pool(&self.config).install(|| {
+            raw_data
+                .par_chunks_mut(self.chunk_size)
+                .enumerate()
+                .try_for_each(|(i, chunk)| -> Result<(), Errors> {
+                    let counter = i + 1;
+                    let pwd = keystream(&pwd, &cipher.nonce, counter as u64);
 
-            self.process_chunk(chunk, &pwd.expose_secret(), key_len as usize)?;
-            drop(pwd);
-        }
+                    cipher.process_chunk(chunk, pwd.expose_secret())
+                })
+        })?;
 
         if self.utils.benchmark {
             println!("Stream encryption took: {:?}", start.elapsed());
@@ -276,20 +493,25 @@ impl CrystalystStream {
         Ok(())
     }
 
+    /// Decrypts data produced by `stream_encrypt` in place.
+    /// - The data is not authenticated.
     pub fn stream_decrypt(&mut self, encrypted_data: &mut [u8]) -> Result<(), Errors> {
         let start = Instant::now();
 
-        let key_len = 32;
-
+        let cipher = ChunkCipher::new(self.config, self.nonce()?);
         let pwd = self.pwd.expose_secret().to_vec();
 
-        for (i, chunk) in encrypted_data.chunks_mut(CHUNK_SIZE).enumerate() {
-            let counter = i + 1;
-            let pwd = keystream(&pwd, &self.nonce, counter as u64);
+        thread_pool(&self.config).install(|| {
+            encrypted_data
+                .par_chunks_mut(self.chunk_size)
+                .enumerate()
+                .try_for_each(|(i, chunk)| -> Result<(), Errors> {
+                    let counter = i + 1;
+                    let pwd = keystream(&pwd, &cipher.nonce, counter as u64);
 
-            self.process_decrypt_chunk(chunk, &pwd.expose_secret(), key_len)?;
-            drop(pwd);
-        }
+                    cipher.process_decrypt_chunk(chunk, pwd.expose_secret())
+                })
+        })?;
 
         if self.utils.benchmark {
             println!("Stream decryption took: {:?}", start.elapsed());
@@ -298,16 +520,126 @@ impl CrystalystStream {
         Ok(())
     }
 
+    /// Encrypts a file into `out_buffer` using the `.cryst` stream file format.
+    ///
+    /// # Format
+    /// - A `StreamHeader` with nonce, a fresh salt, KDF parameters, chunk size and config fingerprint comes first.
+    /// - The password is run through Argon2 with the salt when key derivation is enabled.
+    /// - Every chunk is written as `ciphertext || tag`, full chunks carry `chunk_size` bytes of ciphertext.
+    /// - Chunks are filled completely before they are encrypted, short reads never change chunk boundaries.
+    /// - Every chunk is encrypted under its own key, an HMAC of the stream key over the nonce and chunk index.
+    /// - Tag is HMAC-SHA3-512 over the chunk index, a final-chunk marker and the ciphertext.
+    /// - The final chunk is always shorter than `chunk_size` (it can be empty), so truncation is detected.
+    /// - The header is bound into every tag.
     pub fn stream_file_encrypt(
         &mut self,
         file: File,
         out_buffer: &mut Vec<u8>,
     ) -> Result<(), Errors> {
-        let mut reader = BufReader::new(file);
-        let mut buffer = vec![0u8; 1024 * 1024];
+        self.encrypt_frames(BufReader::new(file), out_buffer)
+    }
 
-        let key_len = 32;
+    /// Decrypts a file produced by `stream_file_encrypt` or `stream_file_to_file_encrypt` into `out_buffer`.
+    /// - Only the password is needed, every other setting is read from the header.
+    /// - Every chunk is authenticated before its plaintext is written.
+    /// - Tampered, reordered or truncated input fails with `Errors::InvalidMac`.
+    pub fn stream_file_decrypt(
+        &mut self,
+        file: File,
+        out_buffer: &mut Vec<u8>,
+    ) -> Result<(), Errors> {
+        self.decrypt_frames(BufReader::new(file), out_buffer)
+    }
+
+    /// Encrypts a file into another file using the authenticated chunk format.
+    /// - See `stream_file_encrypt` for the format.
+    pub fn stream_file_to_file_encrypt(
+        &mut self,
+        file: File,
+        out_buffer: &mut File,
+    ) -> Result<(), Errors> {
+        let mut writer = BufWriter::new(out_buffer);
+        self.encrypt_frames(BufReader::new(file), &mut writer)?;
+
+        writer.flush().map_err(|e| Errors::IoError(e.to_string()))
+    }
+
+    /// Decrypts a file produced by `stream_file_encrypt` or `stream_file_to_file_encrypt` into another file.
+    /// - Only the password is needed, every other setting is read from the header.
+    /// - Every chunk is authenticated before its plaintext is written.
+    /// - Tampered, reordered or truncated input fails with `Errors::InvalidMac`,
+    ///   chunks written before a truncation is detected must be discarded by the caller.
+    pub fn stream_file_to_file_decrypt(
+        &mut self,
+        file: File,
+        out_buffer: &mut File,
+    ) -> Result<(), Errors> {
+        let mut writer = BufWriter::new(out_buffer);
+        self.decrypt_frames(BufReader::new(file), &mut writer)?;
+
+        writer.flush().map_err(|e| Errors::IoError(e.to_string()))
+    }
+
+    /// Encrypts everything from `reader` into `writer` using the `.cryst` stream file format.
+    /// - Works with pipes, sockets and other readers that return short reads.
+    /// - Output is identical in layout to `stream_file_encrypt`, see it for the format.
+    pub fn stream_reader_encrypt<R: Read, W: Write>(
+        &mut self,
+        reader: R,
+        mut writer: W,
+    ) -> Result<(), Errors> {
+        self.encrypt_frames(reader, &mut writer)?;
 
+        writer.flush().map_err(|e| Errors::IoError(e.to_string()))
+    }
+
+    /// Decrypts a `.cryst` stream from `reader` into `writer`.
+    /// - Works with pipes, sockets and other readers that return short reads.
+    /// - Tampered, reordered or truncated input fails with `Errors::InvalidMac`,
+    ///   chunks written before a truncation is detected must be discarded by the caller.
+    pub fn stream_reader_decrypt<R: Read, W: Write>(
+        &mut self,
+        reader: R,
+        mut writer: W,
+    ) -> Result<(), Errors> {
+        self.decrypt_frames(reader, &mut writer)?;
+
+        writer.flush().map_err(|e| Errors::IoError(e.to_string()))
+    }
+
+    /// Re-encrypts a `.cryst` file under `new_password` into another file, the plaintext never leaves the library.
+    /// - See `stream_reader_rekey` for details.
+    pub fn stream_file_rekey(
+        &mut self,
+        new_password: &[u8],
+        file: File,
+        out_buffer: &mut File,
+    ) -> Result<(), Errors> {
+        let mut writer = BufWriter::new(out_buffer);
+        self.rekey_frames(new_password, BufReader::new(file), &mut writer)?;
+
+        writer.flush().map_err(|e| Errors::IoError(e.to_string()))
+    }
+
+    /// Re-encrypts a `.cryst` stream from `reader` into `writer` under `new_password`.
+    /// - The stream password is the old one, every chunk is authenticated before it is re-encrypted.
+    /// - A fresh nonce and salt are generated, chunk size and cipher settings are carried over from the old header.
+    /// - Each chunk is decrypted and re-encrypted in the same buffer, plaintext is zeroized on failure
+    ///   according to `Config::zeroize`.
+    /// - Tampered, reordered or truncated input fails with `Errors::InvalidMac`,
+    ///   chunks written before the failure must be discarded by the caller.
+    pub fn stream_reader_rekey<R: Read, W: Write>(
+        &mut self,
+        new_password: &[u8],
+        reader: R,
+        mut writer: W,
+    ) -> Result<(), Errors> {
+        self.rekey_frames(new_password, reader, &mut writer)?;
+
+        writer.flush().map_err(|e| Errors::IoError(e.to_string()))
+    }
+
+    fn check_password(&self, key_len: u32) -> Result<(), Errors> {
         if (self.pwd.expose_secret().len() as u64)
             .ct_ne(&0)
             .unwrap_u8()
@@ -325,28 +657,68 @@ impl CrystalystStream {
             )));
         }
 
-        let start = Instant::now();
-        let mut counter = 1;
-        let mut pwd = self.pwd.expose_secret().to_vec();
+        Ok(())
+    }
 
-        loop {
-            let bytes_read = reader
-                .read(&mut buffer)
-                .map_err(|_| Errors::EmptyPassword)?;
+    fn encrypt_frames<R: Read, W: Write>(&self, reader: R, writer: &mut W) -> Result<(), Errors> {
+        self.check_password(32)?;
 
-            if bytes_read == 0 {
-                break;
-            }
+        let start = Instant::now();
+        let nonce = self.nonce()?;
+        let header = Header::from_config(&self.config, Some(nonce.as_nonce()), Some(Salt::salt()))?;
+        let stream_header = StreamHeader::new(self.chunk_size, header)?;
+        let header = stream_header.to_bytes();
+        let keys = self.stream_keys(&stream_header, &header)?;
 
-            let pwd = keystream(&mut pwd, &self.nonce, counter);
+        writer
+            .write_all(&header)
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+
+        self.run_pipeline(
+            reader,
+            writer,
+            self.chunk_size,
+            0,
+            |chunk, counter, is_final| {
+                let tag = keys.encrypt_chunk(chunk, counter, is_final)?;
+                chunk.extend_from_slice(&tag);
+                Ok(())
+            },
+        )?;
 
-            let chunk = &mut buffer[..bytes_read];
-            self.process_chunk(chunk, &pwd.expose_secret(), key_len as usize)?;
-            drop(pwd);
-            out_buffer.extend_from_slice(chunk);
-            counter += 1;
+        if self.utils.benchmark {
+            println!("{:?}", start.elapsed());
         }
 
+        Ok(())
+    }
+
+    fn decrypt_frames<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        writer: &mut W,
+    ) -> Result<(), Errors> {
+        self.check_password(32)?;
+
+        let start = Instant::now();
+        let (stream_header, header) = StreamHeader::read_from(&mut reader)?;
+        let keys = self.stream_keys(&stream_header, &header)?;
+        let chunk_size = stream_header.chunk_size as usize;
+
+        self.run_pipeline(
+            reader,
+            writer,
+            chunk_size + TAG_SIZE,
+            TAG_SIZE,
+            |frame, counter, is_final| {
+                let chunk_len = frame.len() - TAG_SIZE;
+                let (chunk, tag) = frame.split_at_mut(chunk_len);
+                keys.decrypt_chunk(chunk, tag, counter, is_final)?;
+                frame.truncate(chunk_len);
+                Ok(())
+            },
+        )?;
+
         if self.utils.benchmark {
             println!("{:?}", start.elapsed());
         }
@@ -354,37 +726,61 @@ impl CrystalystStream {
         Ok(())
     }
 
-    pub fn stream_file_decrypt(
-        &mut self,
-        file: File,
-        out_buffer: &mut Vec<u8>,
+    fn rekey_frames<R: Read, W: Write>(
+        &self,
+        new_password: &[u8],
+        mut reader: R,
+        writer: &mut W,
     ) -> Result<(), Errors> {
-        let mut reader = BufReader::new(file);
-        let mut buffer = vec![0u8; 1024 * 1024];
-
-        let key_len = 32;
+        self.check_password(32)?;
 
         let start = Instant::now();
-        let mut counter = 1;
-        let mut pwd = self.pwd.expose_secret().to_vec();
-
-        loop {
-            let bytes_read = reader
-                .read(&mut buffer)
-                .map_err(|_| Errors::EmptyPassword)?;
+        let (old_header, header) = StreamHeader::read_from(&mut reader)?;
+        let old_keys = self.stream_keys(&old_header, &header)?;
+        let chunk_size = old_header.chunk_size as usize;
+        let config = old_header.header.apply(self.config);
 
-            if bytes_read == 0 {
-                break;
-            }
+        let target = CrystalystStream {
+            config,
+            pwd: KeyBuffer::new(new_password.to_vec()),
+            nonce: None,
+            chunk_size,
+            chunks_in_flight: self.chunks_in_flight,
+            utils: CrystalystStreamUtils::new(false),
+        };
+        target.check_password(32)?;
 
-            let pwd = keystream(&mut pwd, &self.nonce, counter as u64);
+        let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic)?;
+        let header = Header::from_config(&config, Some(nonce), Some(Salt::salt()))?;
+        let new_header = StreamHeader::new(chunk_size, header)?;
+        let header = new_header.to_bytes();
+        let new_keys = target.stream_keys(&new_header, &header)?;
 
-            let chunk = &mut buffer[..bytes_read];
-            self.process_decrypt_chunk(chunk, &pwd.expose_secret(), key_len as usize)?;
-            drop(pwd);
-            out_buffer.extend_from_slice(chunk);
-            counter += 1;
-        }
+        writer
+            .write_all(&header)
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+
+        self.run_pipeline(
+            reader,
+            writer,
+            chunk_size + TAG_SIZE,
+            TAG_SIZE,
+            |frame, counter, is_final| {
+                let chunk_len = frame.len() - TAG_SIZE;
+                let (chunk, tag) = frame.split_at_mut(chunk_len);
+                let new_tag = old_keys
+                    .decrypt_chunk(chunk, tag, counter, is_final)
+                    .and_then(|_| new_keys.encrypt_chunk(chunk, counter, is_final));
+                match new_tag {
+                    Ok(new_tag) => tag.copy_from_slice(&new_tag),
+                    Err(e) => {
+                        secure_zeroize(chunk, &config);
+                        return Err(e);
+                    }
+                }
+                Ok(())
+            },
+        )?;
 
         if self.utils.benchmark {
             println!("{:?}", start.elapsed());
@@ -392,113 +788,394 @@ impl CrystalystStream {
 
         Ok(())
     }
+}
 
-    pub fn stream_file_to_file_encrypt(
-        &mut self,
-        file: File,
-        out_buffer: &mut File,
-    ) -> Result<(), Errors> {
-        let mut reader = BufReader::new(file);
-        let mut writer = BufWriter::new(out_buffer);
-        let mut buffer = vec![0u8; 1024 * 1024];
+impl CrystalystStream {
+    /// Runs frames through a pool of workers, the calling thread reads them and writes the results in order.
+    /// - Frames are `frame_size` bytes, the first shorter frame is the final one.
+    /// - A fixed set of buffers is recycled between the stages, so memory is bounded by `chunks_in_flight`.
+    /// - Workers run on the config's thread pool, at most one per pool thread.
+    /// - Only the workers run on other threads, so `reader` and `writer` do not need to be `Send`.
+    /// - Errors are reported in stream order, frames before a failing one are still written.
+    fn run_pipeline<R, W, F>(
+        &self,
+        mut reader: R,
+        writer: &mut W,
+        frame_size: usize,
+        min_frame: usize,
+        process: F,
+    ) -> Result<(), Errors>
+    where
+        R: Read,
+        W: Write,
+        F: Fn(&mut Vec<u8>, u64, bool) -> Result<(), Errors> + Sync,
+    {
+        let pool = thread_pool(&self.config);
+        let workers = self.workers().min(pool.current_num_threads());
+        let in_flight = self.chunks_in_flight.unwrap_or(workers * 2);
+
+        let (job_tx, job_rx) = mpsc::sync_channel::<(u64, bool, Vec<u8>)>(in_flight);
+        let job_rx = Mutex::new(job_rx);
+        let (done_tx, done_rx) = mpsc::channel::<(u64, bool, Result<Vec<u8>, Errors>)>();
+
+        pool.in_place_scope(|scope| {
+            // Owned by this closure, so returning early stops the workers.
+            let job_tx = job_tx;
+
+            for _ in 0..workers {
+                let job_rx = &job_rx;
+                let done_tx = done_tx.clone();
+                let process = &process;
+                scope.spawn(move |_| {
+                    loop {
+                        let job = job_rx
+                            .lock()
+                            .map_err(|_| ())
+                            .and_then(|rx| rx.recv().map_err(|_| ()));
+                        let Ok((counter, is_final, mut buffer)) = job else {
+                            break;
+                        };
+
+                        let result = process(&mut buffer, counter, is_final).map(|_| buffer);
+                        if done_tx.send((counter, is_final, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(done_tx);
+
+            let mut buffers: Vec<Vec<u8>> = (0..in_flight)
+                .map(|_| Vec::with_capacity(frame_size + TAG_SIZE))
+                .collect();
+            let mut pending: BTreeMap<u64, (bool, Result<Vec<u8>, Errors>)> = BTreeMap::new();
+            let mut counter = 1;
+            let mut next = 1;
+            let mut reading = true;
+
+            loop {
+                while reading {
+                    let Some(mut buffer) = buffers.pop() else {
+                        break;
+                    };
+
+                    match read_frame(&mut reader, &mut buffer, frame_size, min_frame) {
+                        Ok(is_final) => {
+                            reading = job_tx.send((counter, is_final, buffer)).is_ok() && !is_final;
+                        }
+                        Err(e) => {
+                            pending.insert(counter, (true, Err(e)));
+                            reading = false;
+                        }
+                    }
+                    counter += 1;
+                }
 
-        let key_len = 32;
+                while let Some((is_final, result)) = pending.remove(&next) {
+                    let buffer = result?;
+                    writer
+                        .write_all(&buffer)
+                        .map_err(|e| Errors::IoError(e.to_string()))?;
 
-        if (self.pwd.expose_secret().len() as u64)
-            .ct_ne(&0)
-            .unwrap_u8()
-            != 1
-        {
-            return Err(Errors::EmptyPassword);
-        } else if (self.pwd.expose_secret().len() as u32)
-            .ct_lt(&key_len)
-            .unwrap_u8()
-            == 1
-        {
-            return Err(Errors::PasswordTooShort(format!(
-                "Password must be at least {} characters for cryptographic strength.",
-                key_len
-            )));
-        }
+                    if is_final {
+                        return Ok(());
+                    }
 
-        let start = Instant::now();
-        let mut counter = 1;
-        let pwd = self.pwd.expose_secret().to_vec();
+                    buffers.push(buffer);
+                    next += 1;
+                }
 
-        loop {
-            let bytes_read = reader
-                .read(&mut buffer)
-                .map_err(|_| Errors::EmptyPassword)?;
+                if reading && !buffers.is_empty() {
+                    continue;
+                }
 
-            if bytes_read == 0 {
-                break;
+                match done_rx.recv() {
+                    Ok((counter, is_final, result)) => {
+                        pending.insert(counter, (is_final, result));
+                    }
+                    Err(_) => {
+                        return Err(Errors::ChunkError(
+                            "Stream pipeline stopped before the final chunk".to_string(),
+                        ));
+                    }
+                }
             }
+        })
+    }
+}
 
-            let pwd = keystream(&pwd, &self.nonce, counter);
+/// Reads the next frame into `buffer`, returns `true` if it is the final one.
+fn read_frame<R: Read>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    frame_size: usize,
+    min_frame: usize,
+) -> Result<bool, Errors> {
+    buffer.resize(frame_size, 0);
+    let bytes_read = read_chunk(reader, buffer)?;
+    buffer.truncate(bytes_read);
+    let is_final = bytes_read < frame_size;
+
+    if bytes_read < min_frame {
+        Err(Errors::InvalidMac(
+            "Stream truncated: final chunk is missing".to_string(),
+        ))
+    } else if is_final && read_chunk(reader, &mut [0u8; 1])? != 0 {
+        Err(Errors::InvalidMac(
+            "Unexpected data after final chunk".to_string(),
+        ))
+    } else {
+        Ok(is_final)
+    }
+}
 
-            let chunk = &mut buffer[..bytes_read];
-            self.process_chunk(chunk, &pwd.expose_secret(), key_len as usize)?;
-            drop(pwd);
-            writer
-                .write(chunk)
-                .map_err(|_| Errors::BuildFailed("Cannot Write Data".to_string()))?;
-            counter += 1;
+/// Incremental `.cryst` stream encryptor, created with `CrystalystStream::writer`.
+/// - `flush` only flushes the inner writer, partial chunks stay buffered until they are full or `finish` is called.
+/// - If a chunk fails to encrypt or write, the stream is incomplete and every later `write` or `finish` fails.
+pub struct CrystalystWriter<W: Write> {
+    inner: W,
+    keys: StreamKeys,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    counter: u64,
+    poisoned: bool,
+}
+
+impl<W: Write> CrystalystWriter<W> {
+    /// Writes the final chunk and returns the inner writer.
+    pub fn finish(mut self) -> Result<W, Errors> {
+        self.write_chunk(true)?;
+        self.inner
+            .flush()
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+
+        Ok(self.inner)
+    }
+
+    fn write_chunk(&mut self, is_final: bool) -> Result<(), Errors> {
+        self.check_poisoned()?;
+
+        // The buffer holds ciphertext from here on, it must never be encrypted again.
+        self.poisoned = true;
+        let tag = self
+            .keys
+            .encrypt_chunk(&mut self.buffer, self.counter, is_final)?;
+        self.inner
+            .write_all(&self.buffer)
+            .and_then(|_| self.inner.write_all(&tag))
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+
+        self.buffer.clear();
+        self.counter += 1;
+        self.poisoned = false;
+
+        Ok(())
+    }
+
+    fn check_poisoned(&self) -> Result<(), Errors> {
+        if self.poisoned {
+            return Err(Errors::IoError(
+                "Stream writer failed earlier, the output is incomplete".to_string(),
+            ));
         }
 
-        if self.utils.benchmark {
-            println!("{:?}", start.elapsed());
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for CrystalystWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check_poisoned().map_err(io::Error::other)?;
+
+        let n = buf.len().min(self.chunk_size - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..n]);
+
+        if self.buffer.len() == self.chunk_size {
+            self.write_chunk(false).map_err(io::Error::other)?;
         }
 
-        writer
-            .flush()
-            .map_err(|_| Errors::BuildFailed("Cannot flush writer".to_string()))?;
+        Ok(n)
+    }
 
-        Ok(())
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
+}
 
-    pub fn stream_file_to_file_decrypt(
-        &mut self,
-        file: File,
-        out_buffer: &mut File,
-    ) -> Result<(), Errors> {
-        let mut reader = BufReader::new(file);
-        let mut writer = BufWriter::new(out_buffer);
-        let mut buffer = vec![0u8; 1024 * 1024];
+/// Incremental `.cryst` stream decryptor, created with `CrystalystStream::reader`.
+/// - Authentication failures are returned as `std::io::ErrorKind::InvalidData` errors wrapping `Errors`.
+/// - A truncated stream fails when its end is reached, data read before that must be discarded.
+pub struct CrystalystReader<R: Read> {
+    inner: R,
+    keys: StreamKeys,
+    frame_size: usize,
+    buffer: Vec<u8>,
+    pos: usize,
+    counter: u64,
+    done: bool,
+}
 
-        let key_len = 32;
+impl<R: Read> CrystalystReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
 
-        let start = Instant::now();
-        let mut counter = 1;
-        let pwd = self.pwd.expose_secret().to_vec();
+    fn read_frame(&mut self) -> Result<(), Errors> {
+        self.buffer.resize(self.frame_size, 0);
+        self.pos = 0;
+
+        let bytes_read = read_chunk(&mut self.inner, &mut self.buffer)?;
+        if bytes_read < TAG_SIZE {
+            return Err(Errors::InvalidMac(
+                "Stream truncated: final chunk is missing".to_string(),
+            ));
+        }
 
-        loop {
-            let bytes_read = reader
-                .read(&mut buffer)
-                .map_err(|_| Errors::EmptyPassword)?;
+        let is_final = bytes_read < self.frame_size;
+        let chunk_len = bytes_read - TAG_SIZE;
+        let (chunk, tag) = self.buffer[..bytes_read].split_at_mut(chunk_len);
+        self.keys
+            .decrypt_chunk(chunk, tag, self.counter, is_final)?;
+        self.buffer.truncate(chunk_len);
+
+        if is_final && read_chunk(&mut self.inner, &mut [0u8; 1])? != 0 {
+            return Err(Errors::InvalidMac(
+                "Unexpected data after final chunk".to_string(),
+            ));
+        }
 
-            if bytes_read == 0 {
-                break;
-            }
+        self.counter += 1;
+        self.done = is_final;
 
-            let pwd = keystream(&pwd, &self.nonce, counter as u64);
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for CrystalystReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.buffer.len() {
+            if self.done || buf.is_empty() {
+                return Ok(0);
+            }
 
-            let chunk = &mut buffer[..bytes_read];
-            self.process_decrypt_chunk(chunk, &pwd.expose_secret(), key_len as usize)?;
-            drop(pwd);
-            writer
-                .write(chunk)
-                .map_err(|_| Errors::BuildFailed("Cannot Write Data".to_string()))?;
-            counter += 1;
+            if let Err(e) = self.read_frame() {
+                self.buffer.clear();
+                self.pos = 0;
+                return Err(io::Error::new(ErrorKind::InvalidData, e));
+            }
         }
 
-        writer
-            .flush()
-            .map_err(|_| Errors::BuildFailed("Cannot flush writer".to_string()))?;
+        let n = buf.len().min(self.buffer.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+        self.pos += n;
 
-        if self.utils.benchmark {
-            println!("{:?}", start.elapsed());
+        Ok(n)
+    }
+}
+
+/// Random-access reader over a `.cryst` stream file.
+/// - Created with `CrystalystStream::seekable_reader`.
+/// - Only the chunks covering the bytes being read are loaded, authenticated and decrypted.
+/// - Authentication failures are returned as `std::io::ErrorKind::InvalidData` errors wrapping `Errors`.
+pub struct CrystalystSeekReader<R: Read + Seek> {
+    inner: R,
+    keys: StreamKeys,
+    data_start: u64,
+    chunk_size: u64,
+    chunk_count: u64,
+    len: u64,
+    pos: u64,
+    chunk: Vec<u8>,
+    chunk_index: Option<u64>,
+}
+
+impl<R: Read + Seek> CrystalystSeekReader<R> {
+    /// Returns the plaintext length of the stream.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn load_chunk(&mut self, index: u64) -> Result<(), Errors> {
+        if self.chunk_index == Some(index) {
+            return Ok(());
+        }
+        self.chunk_index = None;
+
+        let is_final = index + 1 == self.chunk_count;
+        let chunk_len = if is_final {
+            self.len - index * self.chunk_size
+        } else {
+            self.chunk_size
+        } as usize;
+        let frame_size = self.chunk_size + TAG_SIZE as u64;
+
+        self.chunk.resize(chunk_len + TAG_SIZE, 0);
+        self.inner
+            .seek(SeekFrom::Start(self.data_start + index * frame_size))
+            .map_err(|e| Errors::IoError(e.to_string()))?;
+        if read_chunk(&mut self.inner, &mut self.chunk)? != self.chunk.len() {
+            return Err(Errors::InvalidMac(
+                "Stream truncated while reading".to_string(),
+            ));
         }
 
+        let (chunk, tag) = self.chunk.split_at_mut(chunk_len);
+        self.keys.decrypt_chunk(chunk, tag, index + 1, is_final)?;
+        self.chunk.truncate(chunk_len);
+        self.chunk_index = Some(index);
+
         Ok(())
     }
+
+    fn read_plain(&mut self, buf: &mut [u8]) -> Result<usize, Errors> {
+        if buf.is_empty() || self.pos >= self.len {
+            return Ok(0);
+        }
+
+        let index = self.pos / self.chunk_size;
+        self.load_chunk(index)?;
+
+        let start = (self.pos - index * self.chunk_size) as usize;
+        let n = buf.len().min(self.chunk.len() - start);
+        buf[..n].copy_from_slice(&self.chunk[start..start + n]);
+        self.pos += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Read for CrystalystSeekReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_plain(buf)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+impl<R: Read + Seek> Seek for CrystalystSeekReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+
+        self.pos = pos.ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+
+        Ok(self.pos)
+    }
 }
@@ -0,0 +1,510 @@
+//! # Async Stream Adapters
+//!
+//! `futures-io` versions of `CrystalystWriter` and `CrystalystReader`, enabled with the `async` feature.
+//! - Key derivation and chunk transforms run on the thread pool of `Config::thread_strategy`,
+//!   the I/O task only moves bytes.
+//! - Output is the same `.cryst` format as every other stream function.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use futures_io::{AsyncRead, AsyncWrite};
+use rayon::ThreadPool;
+
+use super::{CrystalystStream, StreamKeys, TAG_SIZE};
+use crate::{
+    Errors,
+    cipher::header::{Header, STREAM_PREFIX_LEN, StreamHeader},
+    engine::engine::thread_pool,
+    rng_utils::{nonce::AsNonce, salt::Salt},
+};
+
+/// CPU work running on a rayon thread pool, polled from an async task.
+struct Offload<T> {
+    slot: Arc<Mutex<(Option<T>, Option<Waker>)>>,
+}
+
+impl<T: Send + 'static> Offload<T> {
+    fn spawn<F: FnOnce() -> T + Send + 'static>(pool: &ThreadPool, job: F) -> Self {
+        let slot = Arc::new(Mutex::new((None, None::<Waker>)));
+        let job_slot = Arc::clone(&slot);
+
+        pool.spawn(move || {
+            let result = job();
+            let mut slot = job_slot.lock().unwrap_or_else(|e| e.into_inner());
+            slot.0 = Some(result);
+            if let Some(waker) = slot.1.take() {
+                waker.wake();
+            }
+        });
+
+        Self { slot }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<T> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.0.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Reads from `inner` until `buffer` holds `target` bytes.
+/// - Returns `false` if the reader ended first.
+fn poll_fill<R: AsyncRead + Unpin>(
+    inner: &mut R,
+    cx: &mut Context<'_>,
+    buffer: &mut Vec<u8>,
+    target: usize,
+) -> Poll<io::Result<bool>> {
+    while buffer.len() < target {
+        let start = buffer.len();
+        buffer.resize(target, 0);
+
+        let result = Pin::new(&mut *inner).poll_read(cx, &mut buffer[start..]);
+        let bytes_read = match result {
+            Poll::Ready(Ok(n)) => n,
+            Poll::Ready(Err(e)) => {
+                buffer.truncate(start);
+                return Poll::Ready(Err(e));
+            }
+            Poll::Pending => {
+                buffer.truncate(start);
+                return Poll::Pending;
+            }
+        };
+
+        buffer.truncate(start + bytes_read);
+        if bytes_read == 0 {
+            return Poll::Ready(Ok(false));
+        }
+    }
+
+    Poll::Ready(Ok(true))
+}
+
+fn derive_keys(
+    stream: &CrystalystStream,
+    stream_header: StreamHeader,
+    header: Vec<u8>,
+) -> Offload<Result<StreamKeys, Errors>> {
+    let stream = CrystalystStream::open(stream.config, stream.pwd.expose_secret());
+    Offload::spawn(&thread_pool(&stream.config), move || {
+        stream.stream_keys(&stream_header, &header)
+    })
+}
+
+impl CrystalystStream {
+    /// Creates an async writer that encrypts everything written to it into `inner` as a `.cryst` stream.
+    /// - Key derivation and encryption run on the thread pool of `Config::thread_strategy`.
+    /// - `close` must be called, otherwise the stream is truncated and fails to decrypt.
+    pub fn async_writer<W: AsyncWrite + Unpin>(
+        &self,
+        inner: W,
+    ) -> Result<AsyncCrystalystWriter<W>, Errors> {
+        self.check_password(32)?;
+
+        let nonce = self.nonce()?;
+        let header = Header::from_config(&self.config, Some(nonce.as_nonce()), Some(Salt::salt()))?;
+        let stream_header = StreamHeader::new(self.chunk_size, header)?;
+        let header = stream_header.to_bytes();
+
+        Ok(AsyncCrystalystWriter {
+            inner,
+            keys: None,
+            keys_job: Some(derive_keys(self, stream_header, header.clone())),
+            pool: thread_pool(&self.config),
+            chunk_job: None,
+            chunk_size: self.chunk_size,
+            buffer: Vec::with_capacity(self.chunk_size),
+            out: header,
+            out_pos: 0,
+            counter: 1,
+            finished: false,
+            poisoned: false,
+        })
+    }
+
+    /// Creates an async reader that decrypts a `.cryst` stream from `inner`.
+    /// - The header is read on the first poll, key derivation and decryption run on the thread pool
+    ///   of `Config::thread_strategy`.
+    pub fn async_reader<R: AsyncRead + Unpin>(
+        &self,
+        inner: R,
+    ) -> Result<AsyncCrystalystReader<R>, Errors> {
+        self.check_password(32)?;
+
+        Ok(AsyncCrystalystReader {
+            inner,
+            stream: CrystalystStream::open(self.config, self.pwd.expose_secret()),
+            pool: thread_pool(&self.config),
+            state: ReadState::Header,
+            keys: None,
+            frame: Vec::new(),
+            frame_size: 0,
+            plain: Vec::new(),
+            pos: 0,
+            counter: 1,
+        })
+    }
+}
+
+/// Async `.cryst` stream encryptor, created with `CrystalystStream::async_writer`.
+/// - `flush` only flushes complete chunks, the partial chunk is written by `close`.
+/// - If a chunk fails to encrypt or write, the stream is incomplete and every later call fails.
+pub struct AsyncCrystalystWriter<W: AsyncWrite + Unpin> {
+    inner: W,
+    keys: Option<Arc<StreamKeys>>,
+    keys_job: Option<Offload<Result<StreamKeys, Errors>>>,
+    chunk_job: Option<Offload<Result<Vec<u8>, Errors>>>,
+    pool: Arc<ThreadPool>,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    out: Vec<u8>,
+    out_pos: usize,
+    counter: u64,
+    finished: bool,
+    poisoned: bool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncCrystalystWriter<W> {
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes pending output and waits for running jobs until nothing is left in flight.
+    /// - Any error poisons the writer, the chunk it concerns is lost.
+    fn poll_progress(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let result = self.poll_jobs(cx);
+        if let Poll::Ready(Err(_)) = result {
+            self.poisoned = true;
+        }
+        result
+    }
+
+    fn poll_jobs(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            while self.out_pos < self.out.len() {
+                let written =
+                    match Pin::new(&mut self.inner).poll_write(cx, &self.out[self.out_pos..]) {
+                        Poll::Ready(result) => result?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                if written == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                }
+                self.out_pos += written;
+            }
+
+            if let Some(job) = &self.keys_job {
+                let keys = match job.poll(cx) {
+                    Poll::Ready(keys) => keys,
+                    Poll::Pending => return Poll::Pending,
+                };
+                self.keys_job = None;
+                self.keys = Some(Arc::new(keys.map_err(io::Error::other)?));
+                continue;
+            }
+
+            if let Some(job) = &self.chunk_job {
+                let frame = match job.poll(cx) {
+                    Poll::Ready(frame) => frame,
+                    Poll::Pending => return Poll::Pending,
+                };
+                self.chunk_job = None;
+                self.out = frame.map_err(io::Error::other)?;
+                self.out_pos = 0;
+                continue;
+            }
+
+            return Poll::Ready(Ok(()));
+        }
+    }
+
+    fn start_chunk(&mut self, is_final: bool) -> io::Result<()> {
+        let keys = self
+            .keys
+            .clone()
+            .ok_or_else(|| io::Error::other("Stream keys are not ready"))?;
+        let mut chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.chunk_size));
+        let counter = self.counter;
+
+        self.chunk_job = Some(Offload::spawn(&self.pool, move || {
+            let tag = keys.encrypt_chunk(&mut chunk, counter, is_final)?;
+            chunk.extend_from_slice(&tag);
+            Ok(chunk)
+        }));
+        self.counter += 1;
+
+        Ok(())
+    }
+
+    fn check_poisoned(&self) -> io::Result<()> {
+        if self.poisoned {
+            return Err(io::Error::other(
+                "Stream writer failed earlier, the output is incomplete",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Runs an operation on the inner writer, poisoning this writer if it fails.
+    fn poll_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+        op: fn(Pin<&mut W>, &mut Context<'_>) -> Poll<io::Result<()>>,
+    ) -> Poll<io::Result<()>> {
+        let result = op(Pin::new(&mut self.inner), cx);
+        if let Poll::Ready(Err(_)) = result {
+            self.poisoned = true;
+        }
+        result
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncCrystalystWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.check_poisoned()?;
+
+        if this.finished {
+            return Poll::Ready(Err(io::Error::other("Stream is already closed")));
+        }
+
+        loop {
+            if buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+
+            if this.buffer.len() < this.chunk_size {
+                let n = buf.len().min(this.chunk_size - this.buffer.len());
+                this.buffer.extend_from_slice(&buf[..n]);
+                return Poll::Ready(Ok(n));
+            }
+
+            match this.poll_progress(cx) {
+                Poll::Ready(result) => result?,
+                Poll::Pending => return Poll::Pending,
+            }
+            this.start_chunk(false)?;
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check_poisoned()?;
+
+        match this.poll_progress(cx) {
+            Poll::Ready(result) => result?,
+            Poll::Pending => return Poll::Pending,
+        }
+
+        this.poll_inner(cx, W::poll_flush)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check_poisoned()?;
+
+        loop {
+            match this.poll_progress(cx) {
+                Poll::Ready(result) => result?,
+                Poll::Pending => return Poll::Pending,
+            }
+
+            if this.finished {
+                break;
+            }
+
+            let is_final = this.buffer.len() < this.chunk_size;
+            this.start_chunk(is_final)?;
+            this.finished = is_final;
+        }
+
+        this.poll_inner(cx, W::poll_close)
+    }
+}
+
+enum ReadState {
+    Header,
+    Keys(Offload<Result<StreamKeys, Errors>>),
+    Frame,
+    Chunk(Offload<Result<Vec<u8>, Errors>>, bool),
+    Done,
+    Failed,
+}
+
+/// Async `.cryst` stream decryptor, created with `CrystalystStream::async_reader`.
+/// - Authentication failures are returned as `std::io::ErrorKind::InvalidData` errors wrapping `Errors`.
+/// - A truncated stream fails when its end is reached, data read before that must be discarded.
+pub struct AsyncCrystalystReader<R: AsyncRead + Unpin> {
+    inner: R,
+    stream: CrystalystStream,
+    pool: Arc<ThreadPool>,
+    state: ReadState,
+    keys: Option<Arc<StreamKeys>>,
+    frame: Vec<u8>,
+    frame_size: usize,
+    plain: Vec<u8>,
+    pos: usize,
+    counter: u64,
+}
+
+impl<R: AsyncRead + Unpin> AsyncCrystalystReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Drives the state machine until plaintext is available or the stream ends.
+    fn poll_plain(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Errors>> {
+        let io_error = |e: io::Error| Errors::IoError(e.to_string());
+
+        loop {
+            if self.pos < self.plain.len() {
+                return Poll::Ready(Ok(()));
+            }
+
+            match &self.state {
+                ReadState::Header => {
+                    let target = if self.frame.len() < STREAM_PREFIX_LEN {
+                        STREAM_PREFIX_LEN
+                    } else {
+                        StreamHeader::encoded_len(&self.frame)?
+                    };
+
+                    let filled = match poll_fill(&mut self.inner, cx, &mut self.frame, target) {
+                        Poll::Ready(result) => result.map_err(io_error)?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+                    if !filled {
+                        return Poll::Ready(Err(Errors::InvalidHeader(
+                            "Stream header is truncated".to_string(),
+                        )));
+                    }
+
+                    if target == STREAM_PREFIX_LEN {
+                        continue;
+                    }
+
+                    let (stream_header, header) = StreamHeader::read_from(&mut &self.frame[..])?;
+                    self.frame_size = stream_header.chunk_size as usize + TAG_SIZE;
+                    self.frame.clear();
+                    self.state = ReadState::Keys(derive_keys(&self.stream, stream_header, header));
+                }
+                ReadState::Keys(job) => {
+                    let keys = match job.poll(cx) {
+                        Poll::Ready(keys) => keys?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+                    self.keys = Some(Arc::new(keys));
+                    self.state = ReadState::Frame;
+                }
+                ReadState::Frame => {
+                    // One byte past the frame tells whether this frame is the final one.
+                    let target = self.frame_size + 1;
+                    let filled = match poll_fill(&mut self.inner, cx, &mut self.frame, target) {
+                        Poll::Ready(result) => result.map_err(io_error)?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    if self.frame.len() < TAG_SIZE {
+                        return Poll::Ready(Err(Errors::InvalidMac(
+                            "Stream truncated: final chunk is missing".to_string(),
+                        )));
+                    }
+
+                    let mut frame = std::mem::take(&mut self.frame);
+                    if frame.len() > self.frame_size {
+                        self.frame = frame.split_off(self.frame_size);
+                    }
+                    let is_final = !filled && frame.len() < self.frame_size;
+
+                    let keys = self
+                        .keys
+                        .clone()
+                        .ok_or_else(|| Errors::IoError("Stream keys are not ready".to_string()))?;
+                    let counter = self.counter;
+
+                    self.counter += 1;
+                    self.state = ReadState::Chunk(
+                        Offload::spawn(&self.pool, move || {
+                            let chunk_len = frame.len() - TAG_SIZE;
+                            let (chunk, tag) = frame.split_at_mut(chunk_len);
+                            keys.decrypt_chunk(chunk, tag, counter, is_final)?;
+                            frame.truncate(chunk_len);
+                            Ok(frame)
+                        }),
+                        is_final,
+                    );
+                }
+                ReadState::Chunk(job, is_final) => {
+                    let is_final = *is_final;
+                    let plain = match job.poll(cx) {
+                        Poll::Ready(plain) => plain?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    self.plain = plain;
+                    self.pos = 0;
+                    self.state = match is_final {
+                        true => ReadState::Done,
+                        false => ReadState::Frame,
+                    };
+                }
+                ReadState::Done => return Poll::Ready(Ok(())),
+                ReadState::Failed => {
+                    return Poll::Ready(Err(Errors::InvalidMac(
+                        "Stream already failed authentication".to_string(),
+                    )));
+                }
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncCrystalystReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        match this.poll_plain(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => {
+                this.state = ReadState::Failed;
+                this.plain.clear();
+                this.pos = 0;
+                return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e)));
+            }
+            Poll::Pending => return Poll::Pending,
+        }
+
+        let n = buf.len().min(this.plain.len() - this.pos);
+        buf[..n].copy_from_slice(&this.plain[this.pos..this.pos + n]);
+        this.pos += n;
+
+        Poll::Ready(Ok(n))
+    }
+}
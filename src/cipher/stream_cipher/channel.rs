@@ -0,0 +1,656 @@
+//! # Secure Channel
+//!
+//! `CrystalystChannel` runs a Kyber handshake over any `Read + Write` transport (TCP stream,
+//! Unix socket, in-memory pipe...) and then exchanges encrypted, authenticated records.
+//! - UAKE (`connect`/`accept`) authenticates the server, AKE (`connect_mutual`/`accept_mutual`)
+//!   authenticates both sides.
+//! - Send and receive directions use separate keys derived from the shared secret and the
+//!   handshake transcript.
+//! - Records carry a sequence number and must arrive in order, replayed, reordered or dropped
+//!   records are rejected.
+//! - Every record is encrypted under its own key, derived from the direction key and the full
+//!   sequence number.
+//! - The client picks the cipher settings, they travel in the client hello as a regular `Header`.
+//!   The server rejects more rounds than its own config allows.
+//!
+//! ## Handshake (all integers little-endian)
+//! | Message      | Fields                                                                      |
+//! |--------------|-----------------------------------------------------------------------------|
+//! | Client hello | Magic (`CRCH`), version, mode, Kyber level, header length (2), header, client public key (AKE only), Kyber init message |
+//! | Server hello | Magic (`CRCH`), version, status, Kyber response message (only if status is 0) |
+//!
+//! Both sides then send a `Finished` record (sequence number 0, empty) and verify the peer's.
+//! A peer without the expected keys derives different keys, so the handshake fails there.
+//!
+//! ## Records
+//! | Offset | Size | Field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 4    | Payload length                          |
+//! | 4      | 8    | Sequence number                         |
+//! | 12     | 1    | Flags (close)                           |
+//! | 13     | ..   | Encrypted payload                       |
+//! | ..     | 64   | Tag                                     |
+
+use std::{
+    cmp::Ordering,
+    io::{Read, Write},
+};
+
+use pqc_kyber::Uake;
+use rand::rngs::OsRng;
+use sha3::{Digest, Sha3_512};
+
+use super::{CHUNK_SIZE, ChunkCipher, StreamKeys, TAG_SIZE, chunk_mac_key, read_chunk};
+use crate::{
+    Config, Errors, KeyBuffer, calculate_hmac,
+    cipher::header::Header,
+    utils::kyber::{AkeSession, KyberLevel, KyberModule, KyberPublicKey, SharedSecret},
+    without_key_derivation,
+};
+
+/// Magic bytes identifying a channel handshake message.
+pub const CHANNEL_MAGIC: [u8; 4] = *b"CRCH";
+/// Current channel protocol version.
+pub const CHANNEL_VERSION: u8 = 1;
+/// Largest payload of a single record.
+pub const MAX_RECORD_SIZE: usize = CHUNK_SIZE;
+
+const MODE_UAKE: u8 = 1;
+const MODE_AKE: u8 = 2;
+
+const STATUS_OK: u8 = 0;
+const STATUS_REJECTED: u8 = 1;
+
+const CLIENT_PREFIX_LEN: usize = 4 + 1 + 1 + 1 + 2;
+const MAX_HEADER_LEN: usize = 256;
+const RECORD_HEADER_LEN: usize = 4 + 8 + 1;
+const FLAG_CLOSE: u8 = 1 << 0;
+
+/// Client side state of a running handshake.
+enum ClientExchange<'a> {
+    Uake(Box<Uake>),
+    Ake(&'a KyberModule, AkeSession),
+}
+
+/// Encrypted connection over a `Read + Write` transport, created by a Kyber handshake.
+/// - `send` and `recv` exchange whole records of at most `MAX_RECORD_SIZE` bytes.
+/// - `close` tells the peer the stream ended on purpose, a connection that ends without it
+///   fails in `recv`, so truncation is always detected.
+pub struct CrystalystChannel<S: Read + Write> {
+    stream: S,
+    send_keys: StreamKeys,
+    recv_keys: StreamKeys,
+    send_seq: u64,
+    recv_seq: u64,
+    peer_public: Option<KyberPublicKey>,
+    peer_closed: bool,
+}
+
+impl<S: Read + Write> CrystalystChannel<S> {
+    /// Connects to a server, authenticating only the server (UAKE).
+    /// - `config` sets the record cipher, key derivation is skipped since the keys are random.
+    ///
+    /// # Errors
+    /// Returns `Errors::ChannelError` if the server rejects the handshake or does not hold
+    /// the private key of `server_public`.
+    pub fn connect(
+        stream: S,
+        server_public: &KyberPublicKey,
+        config: Config,
+    ) -> Result<Self, Errors> {
+        Self::client_handshake(stream, None, server_public, config)
+    }
+
+    /// Connects to a server, authenticating both sides (AKE).
+    /// - The server must accept `identity`'s public key in `accept_mutual`.
+    ///
+    /// # Errors
+    /// Returns `Errors::ChannelError` if the server rejects the handshake or either side
+    /// does not hold the expected keys.
+    pub fn connect_mutual(
+        stream: S,
+        identity: &KyberModule,
+        server_public: &KyberPublicKey,
+        config: Config,
+    ) -> Result<Self, Errors> {
+        Self::client_handshake(stream, Some(identity), server_public, config)
+    }
+
+    /// Accepts a UAKE connection, the client stays anonymous.
+    /// - Clients trying AKE are rejected.
+    /// - Cipher settings are taken from the client hello, runtime options from `config`.
+    /// - Clients asking for more rounds than `config.rounds` are rejected.
+    pub fn accept(stream: S, identity: &KyberModule, config: Config) -> Result<Self, Errors> {
+        Self::server_handshake(stream, identity, None, config)
+    }
+
+    /// Accepts an AKE connection from a client holding one of `client_keys`.
+    /// - Clients trying UAKE, presenting another key or asking for more rounds than
+    ///   `config.rounds` are rejected.
+    /// - `peer_public` returns the key the client authenticated with.
+    pub fn accept_mutual(
+        stream: S,
+        identity: &KyberModule,
+        client_keys: &[KyberPublicKey],
+        config: Config,
+    ) -> Result<Self, Errors> {
+        Self::server_handshake(stream, identity, Some(client_keys), config)
+    }
+
+    /// Returns the peer's authenticated public key.
+    /// - Always the server key on the client, `None` on a UAKE server.
+    pub fn peer_public(&self) -> Option<&KyberPublicKey> {
+        self.peer_public.as_ref()
+    }
+
+    /// Encrypts `data` into one record and writes it to the transport.
+    ///
+    /// # Errors
+    /// Returns `Errors::ChannelError` if `data` is larger than `MAX_RECORD_SIZE`.
+    pub fn send(&mut self, data: &[u8]) -> Result<(), Errors> {
+        if data.len() > MAX_RECORD_SIZE {
+            return Err(Errors::ChannelError(format!(
+                "Record must be at most {} bytes, got {}",
+                MAX_RECORD_SIZE,
+                data.len()
+            )));
+        }
+
+        self.write_record(data, false)
+    }
+
+    /// Reads the next record into `output_buffer`.
+    ///
+    /// # Returns
+    /// `false` once the peer closed the channel, `output_buffer` is empty then.
+    ///
+    /// # Errors
+    /// Returns `Errors::InvalidMac` for tampered records, `Errors::ChannelError` for replayed,
+    /// reordered or dropped records or a connection that ended without a close record.
+    pub fn recv(&mut self, output_buffer: &mut Vec<u8>) -> Result<bool, Errors> {
+        output_buffer.clear();
+        if self.peer_closed {
+            return Ok(false);
+        }
+
+        let expected = self.recv_seq;
+        let (_, is_close) = read_record(&mut self.stream, &self.recv_keys, output_buffer, |seq| {
+            check_sequence(seq, expected)
+        })?;
+        self.recv_seq = expected
+            .checked_add(1)
+            .ok_or_else(|| Errors::ChannelError("Sequence numbers exhausted".to_string()))?;
+
+        if is_close {
+            self.peer_closed = true;
+            output_buffer.clear();
+        }
+
+        Ok(!is_close)
+    }
+
+    /// Sends the close record and returns the transport.
+    pub fn close(mut self) -> Result<S, Errors> {
+        self.write_record(&[], true)?;
+        Ok(self.stream)
+    }
+
+    fn write_record(&mut self, data: &[u8], is_close: bool) -> Result<(), Errors> {
+        let seq = self.send_seq;
+        self.send_seq = seq
+            .checked_add(1)
+            .ok_or_else(|| Errors::ChannelError("Sequence numbers exhausted".to_string()))?;
+
+        write_record(&mut self.stream, &self.send_keys, seq, data, is_close)
+    }
+
+    fn client_handshake(
+        mut stream: S,
+        identity: Option<&KyberModule>,
+        server_public: &KyberPublicKey,
+        config: Config,
+    ) -> Result<Self, Errors> {
+        let config = without_key_derivation(config);
+        let header = Header::from_config(&config, None, None)?.to_bytes();
+
+        let (mut exchange, send_a) = match identity {
+            Some(identity) => {
+                let (session, send_a) = identity.ake_client_init(server_public)?;
+                (ClientExchange::Ake(identity, session), send_a)
+            }
+            None => {
+                let server_public = server_public.as_bytes().try_into().map_err(|_| {
+                    Errors::ChannelError(format!(
+                        "Server public key must be {} bytes for {}, got {}",
+                        KyberModule::PUBLIC_KEY_SIZE,
+                        KyberLevel::CURRENT,
+                        server_public.as_bytes().len()
+                    ))
+                })?;
+
+                let mut uake = Box::new(Uake::new());
+                let send_a = uake
+                    .client_init(server_public, &mut OsRng)
+                    .map_err(|e| Errors::KyberError(e.to_string()))?;
+                (ClientExchange::Uake(uake), send_a.to_vec())
+            }
+        };
+
+        let mut client_hello = Vec::new();
+        client_hello.extend_from_slice(&CHANNEL_MAGIC);
+        client_hello.push(CHANNEL_VERSION);
+        client_hello.push(if identity.is_some() {
+            MODE_AKE
+        } else {
+            MODE_UAKE
+        });
+        client_hello.push(KyberLevel::CURRENT.k());
+        client_hello.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        client_hello.extend_from_slice(&header);
+        if let Some(identity) = identity {
+            client_hello.extend_from_slice(identity.public.as_bytes());
+        }
+        client_hello.extend_from_slice(&send_a);
+        write_all(&mut stream, &client_hello)?;
+
+        let mut server_prefix = [0u8; 6];
+        read_exact(&mut stream, &mut server_prefix)?;
+        check_prefix(&server_prefix)?;
+        if server_prefix[5] != STATUS_OK {
+            return Err(Errors::ChannelError(
+                "Server rejected the handshake".to_string(),
+            ));
+        }
+
+        let mut server_send = vec![
+            0u8;
+            match exchange {
+                ClientExchange::Ake(..) => KyberModule::AKE_SERVER_SEND_SIZE,
+                ClientExchange::Uake(_) => KyberModule::SERVER_SEND_KEY_SIZE,
+            }
+        ];
+        read_exact(&mut stream, &mut server_send)?;
+
+        let shared_secret = match &mut exchange {
+            ClientExchange::Ake(identity, session) => {
+                identity.ake_client_confirm(session, &server_send)?;
+                session.shared_secret()?
+            }
+            ClientExchange::Uake(uake) => {
+                let server_send = server_send.as_slice().try_into().map_err(|_| {
+                    Errors::ChannelError("Server message has the wrong size".to_string())
+                })?;
+                uake.client_confirm(server_send)
+                    .map_err(|e| Errors::KyberError(e.to_string()))?;
+                SharedSecret::new(uake.shared_secret.to_vec())
+            }
+        };
+
+        let transcript = transcript(&client_hello, &server_prefix, &server_send);
+        let send_keys = direction_keys(
+            shared_secret.expose_secret(),
+            &transcript,
+            b"client-to-server",
+            config,
+        )?;
+        let recv_keys = direction_keys(
+            shared_secret.expose_secret(),
+            &transcript,
+            b"server-to-client",
+            config,
+        )?;
+
+        Self::finish_handshake(stream, send_keys, recv_keys, Some(server_public.clone()))
+    }
+
+    fn server_handshake(
+        mut stream: S,
+        identity: &KyberModule,
+        client_keys: Option<&[KyberPublicKey]>,
+        config: Config,
+    ) -> Result<Self, Errors> {
+        let mut client_prefix = [0u8; CLIENT_PREFIX_LEN];
+        read_exact(&mut stream, &mut client_prefix)?;
+        check_prefix(&client_prefix)?;
+
+        let mode = client_prefix[5];
+        let header_len = u16::from_le_bytes([client_prefix[7], client_prefix[8]]) as usize;
+        if header_len > MAX_HEADER_LEN {
+            return Err(Errors::ChannelError(format!(
+                "Client header must be at most {} bytes, got {}",
+                MAX_HEADER_LEN, header_len
+            )));
+        }
+
+        let mut header = vec![0u8; header_len];
+        read_exact(&mut stream, &mut header)?;
+
+        let rejection = if mode != MODE_UAKE && mode != MODE_AKE {
+            Some(format!("Unknown handshake mode: {}", mode))
+        } else if client_keys.is_some() != (mode == MODE_AKE) {
+            Some(match client_keys {
+                Some(_) => "Server requires mutual authentication".to_string(),
+                None => "Server does not support mutual authentication".to_string(),
+            })
+        } else if client_prefix[6] != KyberLevel::CURRENT.k() {
+            Some(format!(
+                "Client uses Kyber K={}, this build uses {}",
+                client_prefix[6],
+                KyberLevel::CURRENT
+            ))
+        } else {
+            None
+        };
+        if let Some(rejection) = rejection {
+            reject(&mut stream);
+            return Err(Errors::ChannelError(rejection));
+        }
+
+        let mut client_public = None;
+        if mode == MODE_AKE {
+            let mut public = vec![0u8; KyberModule::PUBLIC_KEY_SIZE];
+            read_exact(&mut stream, &mut public)?;
+            client_public = Some(KyberPublicKey::new(public));
+        }
+
+        let mut send_a = vec![
+            0u8;
+            match mode {
+                MODE_AKE => KyberModule::AKE_SEND_A_SIZE,
+                _ => KyberModule::UAKE_SEND_A_SIZE,
+            }
+        ];
+        read_exact(&mut stream, &mut send_a)?;
+
+        let config = match Header::parse(&header) {
+            Ok((header, _)) if header.rounds as usize > config.rounds => {
+                reject(&mut stream);
+                return Err(Errors::ChannelError(format!(
+                    "Client asks for {} rounds, at most {} are allowed",
+                    header.rounds, config.rounds
+                )));
+            }
+            Ok((header, len)) if len == header_len => without_key_derivation(header.apply(config)),
+            Ok(_) => {
+                reject(&mut stream);
+                return Err(Errors::ChannelError(
+                    "Client header has trailing data".to_string(),
+                ));
+            }
+            Err(e) => {
+                reject(&mut stream);
+                return Err(e);
+            }
+        };
+
+        let accepted = match (&client_public, client_keys) {
+            (Some(public), Some(keys)) => keys.contains(public),
+            _ => true,
+        };
+        if !accepted {
+            reject(&mut stream);
+            return Err(Errors::ChannelError(
+                "Client key is not accepted".to_string(),
+            ));
+        }
+
+        let (shared_secret, server_send) = match &client_public {
+            Some(public) => {
+                let (session, server_send) = identity.ake_server_receive(public, &send_a)?;
+                (session.shared_secret()?, server_send)
+            }
+            None => {
+                let mut module = KyberModule::new_from_existed(
+                    identity.public.as_bytes(),
+                    identity.secret.expose_secret(),
+                    None,
+                )?;
+                let server_send = module.server_receive(send_a.clone())?;
+                (module.shared_secret(), server_send)
+            }
+        };
+
+        let mut server_prefix = [0u8; 6];
+        server_prefix[..4].copy_from_slice(&CHANNEL_MAGIC);
+        server_prefix[4] = CHANNEL_VERSION;
+        server_prefix[5] = STATUS_OK;
+        write_all(&mut stream, &[&server_prefix[..], &server_send].concat())?;
+
+        let mut client_hello = client_prefix.to_vec();
+        client_hello.extend_from_slice(&header);
+        if let Some(public) = &client_public {
+            client_hello.extend_from_slice(public.as_bytes());
+        }
+        client_hello.extend_from_slice(&send_a);
+
+        let transcript = transcript(&client_hello, &server_prefix, &server_send);
+        let send_keys = direction_keys(
+            shared_secret.expose_secret(),
+            &transcript,
+            b"server-to-client",
+            config,
+        )?;
+        let recv_keys = direction_keys(
+            shared_secret.expose_secret(),
+            &transcript,
+            b"client-to-server",
+            config,
+        )?;
+
+        Self::finish_handshake(stream, send_keys, recv_keys, client_public)
+    }
+
+    /// Exchanges the `Finished` records that confirm both sides derived the same keys.
+    fn finish_handshake(
+        mut stream: S,
+        send_keys: StreamKeys,
+        recv_keys: StreamKeys,
+        peer_public: Option<KyberPublicKey>,
+    ) -> Result<Self, Errors> {
+        write_record(&mut stream, &send_keys, 0, &[], false)?;
+
+        let mut finished = Vec::new();
+        read_record(&mut stream, &recv_keys, &mut finished, |seq| match seq {
+            0 => Ok(()),
+            _ => Err(Errors::ChannelError(
+                "Expected the peer's Finished record".to_string(),
+            )),
+        })
+        .map_err(|e| match e {
+            Errors::InvalidMac(_) => Errors::ChannelError(
+                "Handshake failed, peer does not hold the expected keys".to_string(),
+            ),
+            e => e,
+        })?;
+
+        Ok(Self {
+            stream,
+            send_keys,
+            recv_keys,
+            send_seq: 1,
+            recv_seq: 1,
+            peer_public,
+            peer_closed: false,
+        })
+    }
+}
+
+/// Hashes both handshake messages, the hash is bound into every derived key.
+fn transcript(client_hello: &[u8], server_prefix: &[u8], server_send: &[u8]) -> Vec<u8> {
+    let mut hash = Sha3_512::new();
+    hash.update(client_hello);
+    hash.update(server_prefix);
+    hash.update(server_send);
+    hash.finalize().to_vec()
+}
+
+/// Derives the record keys of one direction.
+fn direction_keys(
+    shared_secret: &[u8],
+    transcript: &[u8],
+    direction: &[u8],
+    config: Config,
+) -> Result<StreamKeys, Errors> {
+    let mut info = b"CRYSTALYST-channel-".to_vec();
+    info.extend_from_slice(direction);
+    info.extend_from_slice(transcript);
+    let material = KeyBuffer::new(calculate_hmac(shared_secret, &info)?);
+
+    let key = KeyBuffer::new(material.expose_secret()[..32].to_vec());
+    let mut nonce = [0u8; 32];
+    nonce.copy_from_slice(&material.expose_secret()[32..64]);
+
+    Ok(StreamKeys {
+        mac_key: chunk_mac_key(key.expose_secret(), &nonce, transcript)?,
+        key,
+        cipher: ChunkCipher::new(config, nonce),
+    })
+}
+
+/// Records arrive in order on a stream transport, any other sequence number was replayed or
+/// reordered, or records before it were dropped.
+fn check_sequence(seq: u64, expected: u64) -> Result<(), Errors> {
+    match seq.cmp(&expected) {
+        Ordering::Equal => Ok(()),
+        Ordering::Less => Err(Errors::ChannelError(format!("Record {} was replayed", seq))),
+        Ordering::Greater => Err(Errors::ChannelError(format!(
+            "Record {} arrived while {} was expected, records were dropped",
+            seq, expected
+        ))),
+    }
+}
+
+fn check_prefix(prefix: &[u8]) -> Result<(), Errors> {
+    if prefix[..4] != CHANNEL_MAGIC {
+        Err(Errors::ChannelError(
+            "Peer is not speaking the CRYSTALYST channel protocol".to_string(),
+        ))
+    } else if prefix[4] != CHANNEL_VERSION {
+        Err(Errors::ChannelError(format!(
+            "Unsupported channel version: {}",
+            prefix[4]
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Tells the client the handshake was rejected, errors are ignored since the handshake fails anyway.
+fn reject<S: Write>(stream: &mut S) {
+    let mut server_prefix = [0u8; 6];
+    server_prefix[..4].copy_from_slice(&CHANNEL_MAGIC);
+    server_prefix[4] = CHANNEL_VERSION;
+    server_prefix[5] = STATUS_REJECTED;
+    let _ = write_all(stream, &server_prefix);
+}
+
+fn write_record<S: Write>(
+    stream: &mut S,
+    keys: &StreamKeys,
+    seq: u64,
+    data: &[u8],
+    is_close: bool,
+) -> Result<(), Errors> {
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN + data.len() + TAG_SIZE);
+    record.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    record.extend_from_slice(&seq.to_le_bytes());
+    record.push(if is_close { FLAG_CLOSE } else { 0 });
+    record.extend_from_slice(data);
+
+    let tag = keys.encrypt_chunk(&mut record[RECORD_HEADER_LEN..], seq, is_close)?;
+    record.extend_from_slice(&tag);
+
+    write_all(stream, &record)
+}
+
+/// Reads, authenticates and decrypts one record into `output_buffer`.
+/// - `check_seq` runs before any cryptographic work.
+/// - Rejected records are consumed completely, so the next record can still be read.
+fn read_record<S: Read, F: FnOnce(u64) -> Result<(), Errors>>(
+    stream: &mut S,
+    keys: &StreamKeys,
+    output_buffer: &mut Vec<u8>,
+    check_seq: F,
+) -> Result<(u64, bool), Errors> {
+    let mut record_header = [0u8; RECORD_HEADER_LEN];
+    match read_chunk(stream, &mut record_header)? {
+        0 => {
+            return Err(Errors::ChannelError(
+                "Connection closed without a close record".to_string(),
+            ));
+        }
+        RECORD_HEADER_LEN => {}
+        _ => {
+            return Err(Errors::ChannelError(
+                "Connection closed in the middle of a record".to_string(),
+            ));
+        }
+    }
+
+    let (len, seq) = record_header.split_at(4);
+    let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    let mut seq_bytes = [0u8; 8];
+    seq_bytes.copy_from_slice(&seq[..8]);
+    let seq = u64::from_le_bytes(seq_bytes);
+    let is_close = match record_header[12] {
+        0 => false,
+        FLAG_CLOSE => true,
+        flags => {
+            return Err(Errors::ChannelError(format!(
+                "Unknown record flags: {:#04x}",
+                flags
+            )));
+        }
+    };
+    if len > MAX_RECORD_SIZE {
+        return Err(Errors::ChannelError(format!(
+            "Record must be at most {} bytes, got {}",
+            MAX_RECORD_SIZE, len
+        )));
+    }
+
+    output_buffer.clear();
+    output_buffer.resize(len + TAG_SIZE, 0);
+    if read_chunk(stream, output_buffer)? != output_buffer.len() {
+        output_buffer.clear();
+        return Err(Errors::ChannelError(
+            "Connection closed in the middle of a record".to_string(),
+        ));
+    }
+
+    if let Err(e) = check_seq(seq) {
+        output_buffer.clear();
+        return Err(e);
+    }
+
+    let (data, tag) = output_buffer.split_at_mut(len);
+    if let Err(e) = keys.decrypt_chunk(data, tag, seq, is_close) {
+        output_buffer.clear();
+        return Err(match e {
+            Errors::InvalidMac(_) => {
+                Errors::InvalidMac(format!("Record {} failed authentication", seq))
+            }
+            e => e,
+        });
+    }
+    output_buffer.truncate(len);
+
+    Ok((seq, is_close))
+}
+
+fn read_exact<S: Read>(stream: &mut S, buffer: &mut [u8]) -> Result<(), Errors> {
+    if read_chunk(stream, buffer)? != buffer.len() {
+        return Err(Errors::ChannelError(
+            "Connection closed during the handshake".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn write_all<S: Write>(stream: &mut S, data: &[u8]) -> Result<(), Errors> {
+    stream
+        .write_all(data)
+        .and_then(|_| stream.flush())
+        .map_err(|e| Errors::IoError(e.to_string()))
+}
@@ -10,7 +10,7 @@ pub struct CacheWarmup64 {
     pub inv_sbox: [u8; 256],
 }
 
-#[cfg(target_arch = "aarch64")]
+#[cfg(not(target_arch = "x86_64"))]
 #[repr(align(128))]
 #[repr(C)]
 pub struct CacheWarmup64 {
@@ -89,7 +89,7 @@ impl CacheWarmup for CacheWarmup64 {
     }
 }
 
-#[cfg(target_arch = "aarch64")]
+#[cfg(not(target_arch = "x86_64"))]
 impl CacheWarmup for CacheWarmup64 {
     #[inline(always)]
     fn warm_cache(&self) {
@@ -1,42 +1,31 @@
+#[cfg(target_arch = "x86_64")]
+use crate::engine::simd::{avx2_add_inplace, avx2_sub_inplace, avx2_xor_inplace};
 use crate::{
-    Config, Errors, GaloisFieldType, TpmModule,
-    engine::{
-        cache_warmup::{CacheWarmup, CacheWarmup64},
-        simd::{avx2_add_inplace, avx2_sub_inplace, avx2_xor_inplace},
-    },
+    Config, Errors, TpmModule,
+    engine::cache_warmup::{CacheWarmup, CacheWarmup64},
+    utils::key_cache::KeyCache,
 };
-use dashmap::DashMap;
 use rand::Rng;
 use rayon::{ThreadPool, prelude::*};
-use secrecy::{ExposeSecret, SecretBox};
 use sha3::{Digest, Sha3_512};
 use std::{
-    arch::x86_64::{__m128i, _mm_loadu_si128, _mm_set_epi8, _mm_shuffle_epi8, _mm_storeu_si128},
     hint::black_box,
-    sync::OnceLock,
+    sync::{Arc, Mutex},
     thread,
     time::Duration,
 };
 use subtle::{ConditionallySelectable, ConstantTimeEq};
 use tss_esapi::structures::MaxBuffer;
-use zeroize::Zeroize;
-
-type SecretKey = SecretBox<[u8]>;
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct KeyBuffer(Vec<u8>);
-
-impl Drop for KeyBuffer {
-    fn drop(&mut self) {
-        self.0.zeroize();
-    }
-}
 
 pub const RATIO: u64 = 0x9E3779B97F4A7C15;
 pub const ROTATIONS: [u32; 10] = [8, 2, 3, 1, 4, 5, 12, 9, 11, 4];
 pub const POS_DEPENT: [u32; 10] = [1, 8, 3, 1, 8, 3, 1, 9, 1, 4];
-static THREAD_POOL: OnceLock<ThreadPool> = OnceLock::new();
-static KEY_CACHE_MAP: OnceLock<DashMap<KeyBuffer, SecretKey>> = OnceLock::new();
+/// Number of thread pools kept alive, the least recently used one is dropped beyond that.
+const MAX_THREAD_POOLS: usize = 8;
+/// Pool built for `(threads, stack_size)`.
+type PoolEntry = ((usize, usize), Arc<ThreadPool>);
+/// Cached pools, least recently used first.
+static THREAD_POOLS: Mutex<Vec<PoolEntry>> = Mutex::new(Vec::new());
 
 fn choose_key(nonce: &[u8], key: &[u8], config: &Config) -> Result<Vec<u8>, Errors> {
     match config.hardware.hardware_hashing {
@@ -68,38 +57,50 @@ fn choose_key(nonce: &[u8], key: &[u8], config: &Config) -> Result<Vec<u8>, Erro
     }
 }
 
-fn get_thread_pool(thread_num: usize, stack_size: usize) -> &'static ThreadPool {
-    THREAD_POOL.get_or_init(|| {
-        rayon::ThreadPoolBuilder::new()
-            .num_threads(thread_num)
-            .stack_size(stack_size)
-            .build()
-            .expect("Failed to build thread pool")
-    })
+/// Returns the pool matching `config`'s thread strategy and stack size.
+/// - Pools are shared per `(threads, stack_size)` and built on first use, so a `SingleThread`
+///   config never runs on a wider pool created for an earlier config.
+/// - At most `MAX_THREAD_POOLS` are kept, an evicted pool shuts down once its last user drops it.
+pub fn thread_pool(config: &Config) -> Arc<ThreadPool> {
+    get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size)
 }
 
-pub fn key_cache(nonce: &mut [u8], key: &[u8], config: &Config) -> Result<Vec<u8>, Errors> {
-    let cache = KEY_CACHE_MAP.get_or_init(|| DashMap::new());
-
-    let key_pair = [key, nonce].concat();
+fn get_thread_pool(thread_num: usize, stack_size: usize) -> Arc<ThreadPool> {
+    let key = (thread_num.max(1), stack_size);
+    let mut pools = THREAD_POOLS.lock().unwrap_or_else(|e| e.into_inner());
 
-    if cache.len() > 4 {
-        cache.clear();
-    }
-
-    if let Some(value_entry) = cache.get(&KeyBuffer(key_pair.to_vec())) {
-        let value = value_entry.value();
-        return Ok(value.expose_secret().to_vec());
-    }
+    let entry = match pools.iter().position(|(k, _)| *k == key) {
+        Some(index) => pools.remove(index),
+        None => {
+            if pools.len() >= MAX_THREAD_POOLS {
+                pools.remove(0);
+            }
 
-    let value = choose_key(nonce, key, config)?;
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(key.0)
+                .stack_size(stack_size)
+                .build()
+                .expect("Failed to build thread pool");
+            (key, Arc::new(pool))
+        }
+    };
 
-    cache.insert(
-        KeyBuffer(key_pair.to_vec()),
-        SecretBox::new(value.clone().into_boxed_slice()),
-    );
+    let pool = entry.1.clone();
+    pools.push(entry);
+    pool
+}
 
-    Ok(value)
+/// Derives the S-box seed for `key` and `nonce`, through `cache` when one is given.
+pub fn key_cache(
+    nonce: &[u8],
+    key: &[u8],
+    config: &Config,
+    cache: Option<&KeyCache>,
+) -> Result<Vec<u8>, Errors> {
+    match cache {
+        Some(cache) => cache.get_or_derive(key, nonce, || choose_key(nonce, key, config)),
+        None => choose_key(nonce, key, config),
+    }
 }
 
 #[repr(align(64))]
@@ -256,43 +257,43 @@ fn parallel_sub_inplace(data: &mut [u8], key: &CacheWarmup64, pool: &ThreadPool,
     });
 }
 
+/// Returns `true` if AVX2 is enabled in `config` and supported by the CPU.
+#[cfg(target_arch = "x86_64")]
+pub fn avx2_enabled(config: &Config) -> bool {
+    config.hardware.enable_avx2 && is_x86_feature_detected!("avx2")
+}
+
 fn xor(data: &mut [u8], key: &CacheWarmup64, config: &Config) {
-    if is_x86_feature_detected!("avx2") && config.hardware.enable_avx2 {
-        unsafe { avx2_xor_inplace(data, key, &config) }
-    } else {
-        parallel_xor_inplace(
-            data,
-            key,
-            get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size),
-            &config,
-        )
+    let pool = thread_pool(config);
+
+    #[cfg(target_arch = "x86_64")]
+    if avx2_enabled(config) {
+        return pool.install(|| unsafe { avx2_xor_inplace(data, key, config) });
     }
+
+    parallel_xor_inplace(data, key, &pool, config)
 }
 
 fn add(data: &mut [u8], key: &CacheWarmup64, config: &Config) {
-    if is_x86_feature_detected!("avx2") && config.hardware.enable_avx2 {
-        unsafe { avx2_add_inplace(data, key, config) }
-    } else {
-        parallel_add_inplace(
-            data,
-            key,
-            get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size),
-            &config,
-        )
+    let pool = thread_pool(config);
+
+    #[cfg(target_arch = "x86_64")]
+    if avx2_enabled(config) {
+        return pool.install(|| unsafe { avx2_add_inplace(data, key, config) });
     }
+
+    parallel_add_inplace(data, key, &pool, config)
 }
 
 fn sub(data: &mut [u8], key: &CacheWarmup64, config: &Config) {
-    if is_x86_feature_detected!("avx2") && config.hardware.enable_avx2 {
-        unsafe { avx2_sub_inplace(data, key, config) }
-    } else {
-        parallel_sub_inplace(
-            data,
-            key,
-            get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size),
-            &config,
-        )
+    let pool = thread_pool(config);
+
+    #[cfg(target_arch = "x86_64")]
+    if avx2_enabled(config) {
+        return pool.install(|| unsafe { avx2_sub_inplace(data, key, config) });
     }
+
+    parallel_sub_inplace(data, key, &pool, config)
 }
 
 #[inline]
@@ -330,13 +331,8 @@ pub fn generate_counter_keystream(nonce: u64, block_counter: u64, chunk_idx: usi
     ]
 }
 
-pub fn triangle_mix_columns(
-    data: &mut [u8],
-    gf: &GaloisField,
-    nonce: &[u8],
-    config: &Config,
-) -> Result<(), Errors> {
-    gf.warm_cache();
+/// Allocates and drops a random amount of random bytes, if `config.dummy_data` is set.
+pub fn dummy_noise(config: &Config) {
     let mut _dummy_data: Vec<u8> = Vec::new();
 
     match config.dummy_data {
@@ -347,36 +343,21 @@ pub fn triangle_mix_columns(
         }
         false => {}
     }
+}
 
-    if data.is_empty() {
-        return Err(Errors::GaloisFieldError("Empty Data".to_string()));
-    }
-
-    let pool = get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size);
+/// Key stream of the `idx`-th 3-byte column of the triangular Galois field layer.
+#[inline]
+pub fn triangle_column_stream(gf: &GaloisField, nonce: &[u8], idx: usize) -> [u8; 3] {
+    let block_counter = ((idx / 512) as u64) + 1;
+    let mut key_stream =
+        generate_counter_keystream((nonce[idx % nonce.len()]) as u64, block_counter, idx);
 
-    pool.install(|| {
-        data.par_chunks_exact_mut(3)
-            .enumerate()
-            .for_each(|(idx, chunk)| {
-                let block_counter = ((idx / 512) as u64) + 1;
-                let mut key_stream = generate_counter_keystream(
-                    (nonce[idx % nonce.len()]) as u64,
-                    block_counter,
-                    idx,
-                );
-
-                let [a, b, c] = [key_stream[0], key_stream[1], key_stream[2]];
-                key_stream[0] = gf.fast_multiply(3, a) ^ gf.fast_multiply(4, b) ^ c;
-                key_stream[1] = gf.fast_multiply(4, b) ^ c;
-                key_stream[2] = gf.fast_multiply(6, c);
-
-                for (i, byte) in chunk.iter_mut().enumerate() {
-                    *byte ^= black_box(key_stream[i]);
-                }
-            });
-    });
+    let [a, b, c] = [key_stream[0], key_stream[1], key_stream[2]];
+    key_stream[0] = gf.fast_multiply(3, a) ^ gf.fast_multiply(4, b) ^ c;
+    key_stream[1] = gf.fast_multiply(4, b) ^ c;
+    key_stream[2] = gf.fast_multiply(6, c);
 
-    Ok(())
+    key_stream
 }
 
 #[inline]
@@ -395,123 +376,46 @@ pub fn generate_counter_keystream_aes(nonce: u64, block_counter: u64, chunk_idx:
     ]
 }
 
-pub fn aes_mix_columns(
-    data: &mut [u8],
-    gf: &GaloisField,
-    config: &Config,
-    nonce: &[u8],
-) -> Result<(), Errors> {
-    gf.warm_cache();
-    let mut _dummy_data: Vec<u8> = Vec::new();
-
-    match config.dummy_data {
-        true => {
-            for _i in 0..rand::thread_rng().gen_range(0..=config.dummy_data_size) {
-                _dummy_data.push(rand::random::<u8>());
-            }
-        }
-        false => {}
-    }
-
-    if data.is_empty() {
-        return Err(Errors::GaloisFieldError("Empty Data".to_string()));
-    }
-
-    let pool = get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size);
-
-    pool.install(|| {
-        data.par_chunks_exact_mut(4)
-            .enumerate()
-            .for_each(|(idx, chunk)| {
-                let block_counter = ((idx / 512) as u64) + 1;
-
-                let mut key_stream = generate_counter_keystream_aes(
-                    (nonce[idx % nonce.len()]) as u64,
-                    block_counter,
-                    idx,
-                );
-
-                let [s0, s1, s2, s3] = [key_stream[0], key_stream[1], key_stream[2], key_stream[3]];
-                key_stream[0] = gf.fast_multiply(2, s0) ^ gf.fast_multiply(3, s1) ^ s2 ^ s3;
-                key_stream[1] = s0 ^ gf.fast_multiply(2, s1) ^ gf.fast_multiply(3, s2) ^ s3;
-                key_stream[2] = s0 ^ s1 ^ gf.fast_multiply(2, s2) ^ gf.fast_multiply(3, s3);
-                key_stream[3] = gf.fast_multiply(3, s0) ^ s1 ^ s2 ^ gf.fast_multiply(2, s3);
-
-                for (i, byte) in chunk.iter_mut().enumerate() {
-                    *byte ^= black_box(key_stream[i]);
-                }
-            });
-    });
-
-    Ok(())
-}
-
-pub fn apply_gf(
-    data: &mut [u8],
-    config: &Config,
-    gf: &GaloisField,
-    nonce: &[u8],
-) -> Result<(), Errors> {
-    match config.gf_type {
-        GaloisFieldType::Triangular => triangle_mix_columns(data, gf, nonce, config),
-        GaloisFieldType::AES => aes_mix_columns(data, gf, config, nonce),
-        GaloisFieldType::Hybrid => {
-            aes_mix_columns(data, gf, config, nonce)?;
-            triangle_mix_columns(data, gf, nonce, config)
-        }
-    }
+/// Key stream of the `idx`-th 4-byte column of the AES Galois field layer.
+#[inline]
+pub fn aes_column_stream(gf: &GaloisField, nonce: &[u8], idx: usize) -> [u8; 4] {
+    let block_counter = ((idx / 512) as u64) + 1;
+    let mut key_stream =
+        generate_counter_keystream_aes((nonce[idx % nonce.len()]) as u64, block_counter, idx);
+
+    let [s0, s1, s2, s3] = [key_stream[0], key_stream[1], key_stream[2], key_stream[3]];
+    key_stream[0] = gf.fast_multiply(2, s0) ^ gf.fast_multiply(3, s1) ^ s2 ^ s3;
+    key_stream[1] = s0 ^ gf.fast_multiply(2, s1) ^ gf.fast_multiply(3, s2) ^ s3;
+    key_stream[2] = s0 ^ s1 ^ gf.fast_multiply(2, s2) ^ gf.fast_multiply(3, s3);
+    key_stream[3] = gf.fast_multiply(3, s0) ^ s1 ^ s2 ^ gf.fast_multiply(2, s3);
+
+    key_stream
 }
 
-pub fn inverse_shift_rows(data: &mut [u8], config: &Config) {
-    let pool = get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size);
-
-    if is_x86_feature_detected!("avx2") && config.hardware.enable_avx2 {
-        data.par_chunks_exact_mut(16).for_each(|chunk| unsafe {
-            let mask = _mm_set_epi8(15, 2, 5, 8, 11, 14, 1, 4, 7, 10, 13, 0, 3, 6, 9, 12);
-            let shuffled =
-                _mm_shuffle_epi8(_mm_loadu_si128(chunk.as_ptr() as *const __m128i), mask);
-            _mm_storeu_si128(chunk.as_mut_ptr() as *mut __m128i, shuffled);
-        });
-    } else {
-        pool.install(|| {
-            data.par_chunks_exact_mut(16).for_each(|chunk| {
-                chunk.swap(11, 7);
-                chunk.swap(15, 11);
-                chunk.swap(3, 15);
-                chunk.swap(6, 14);
-                chunk.swap(2, 10);
-                chunk.swap(9, 13);
-                chunk.swap(5, 9);
-                chunk.swap(1, 5);
-            });
-        })
-    }
+/// Inverse ShiftRows of one 16-byte block.
+#[inline]
+pub fn inverse_shift_rows_block(chunk: &mut [u8]) {
+    chunk.swap(11, 7);
+    chunk.swap(15, 11);
+    chunk.swap(3, 15);
+    chunk.swap(6, 14);
+    chunk.swap(2, 10);
+    chunk.swap(9, 13);
+    chunk.swap(5, 9);
+    chunk.swap(1, 5);
 }
 
-pub fn shift_rows(data: &mut [u8], config: &Config) {
-    let pool = get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size);
-
-    if is_x86_feature_detected!("avx2") && config.hardware.enable_avx2 {
-        data.par_chunks_exact_mut(16).for_each(|chunk| unsafe {
-            let mask = _mm_set_epi8(11, 6, 1, 12, 7, 2, 13, 8, 3, 14, 9, 4, 15, 10, 5, 0);
-            let shuffled =
-                _mm_shuffle_epi8(_mm_loadu_si128(chunk.as_ptr() as *const __m128i), mask);
-            _mm_storeu_si128(chunk.as_mut_ptr() as *mut __m128i, shuffled);
-        });
-    } else {
-        pool.install(|| {
-            data.par_chunks_exact_mut(16).for_each(|chunk| {
-                chunk.swap(1, 5);
-                chunk.swap(5, 9);
-                chunk.swap(9, 13);
-                chunk.swap(2, 10);
-                chunk.swap(6, 14);
-                chunk.swap(3, 15);
-                chunk.swap(15, 11);
-                chunk.swap(11, 7);
-            });
-        });
-    }
+/// ShiftRows of one 16-byte block.
+#[inline]
+pub fn shift_rows_block(chunk: &mut [u8]) {
+    chunk.swap(1, 5);
+    chunk.swap(5, 9);
+    chunk.swap(9, 13);
+    chunk.swap(2, 10);
+    chunk.swap(6, 14);
+    chunk.swap(3, 15);
+    chunk.swap(15, 11);
+    chunk.swap(11, 7);
 }
 
 pub fn rxa_encrypt(pwd: &CacheWarmup64, input: &mut [u8], config: Config) -> Result<(), Errors> {
@@ -523,7 +427,7 @@ pub fn rxa_encrypt(pwd: &CacheWarmup64, input: &mut [u8], config: Config) -> Res
         input
     };
 
-    let pool = get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size);
+    let pool = thread_pool(&config);
 
     pool.install(|| {
         input
@@ -546,7 +450,7 @@ pub fn rxa_decrypt(pwd: &CacheWarmup64, input: &mut [u8], config: Config) -> Res
         input
     };
 
-    let pool = get_thread_pool(config.thread_strategy.get_cpu_count(), config.stack_size);
+    let pool = thread_pool(&config);
 
     sub(input, &pwd, &config);
     xor(input, &pwd, &config);
@@ -561,7 +465,7 @@ pub fn rxa_decrypt(pwd: &CacheWarmup64, input: &mut [u8], config: Config) -> Res
 }
 
 #[inline]
-fn constant_time_sbox_lookup(sbox: &[u8; 256], input: u8) -> u8 {
+pub fn constant_time_sbox_lookup(sbox: &[u8; 256], input: u8) -> u8 {
     let mut result = 0u8;
 
     for i in 0u8..=255u8 {
@@ -582,14 +486,18 @@ pub fn generate_inv_s_box(s_box: &[u8; 256]) -> [u8; 256] {
     inv_s_box
 }
 
-pub fn generate_dynamic_sbox(nonce: &[u8], key: &[u8], cfg: Config) -> Result<[u8; 256], Errors> {
+pub fn generate_dynamic_sbox(
+    nonce: &[u8],
+    key: &[u8],
+    cfg: Config,
+    cache: Option<&KeyCache>,
+) -> Result<[u8; 256], Errors> {
     let mut sbox: [u8; 256] = [0; 256];
     for i in 0..256 {
         sbox[i] = i as u8;
     }
 
-    let mut nonce = nonce.to_vec();
-    let seed_base = key_cache(&mut nonce, key, &cfg)?;
+    let seed_base = key_cache(nonce, key, &cfg, cache)?;
     let mut seed = seed_base.iter().map(|b| *b as u32).collect::<Vec<u32>>();
 
     seed.iter_mut().enumerate().for_each(|(i, byte)| {
@@ -610,42 +518,6 @@ pub fn generate_dynamic_sbox(nonce: &[u8], key: &[u8], cfg: Config) -> Result<[u
     Ok(sbox)
 }
 
-pub fn in_s_bytes(data: &mut [u8], inv_sbox: &CacheWarmup64, cfg: Config) -> Result<(), Errors> {
-    let pool = get_thread_pool(cfg.thread_strategy.get_cpu_count(), cfg.stack_size);
-    if cfg.hardware.warmup_cache {
-        inv_sbox.pre_sbox_warmup();
-    }
-
-    match cfg.subtle_sbox {
-        true => Ok(pool.install(|| {
-            data.par_iter_mut()
-                .for_each(|b| *b = constant_time_sbox_lookup(&inv_sbox.inv_sbox, *b));
-        })),
-        false => Ok(pool.install(|| {
-            data.par_iter_mut()
-                .for_each(|b| *b = inv_sbox.cache_inverse_lookup(*b));
-        })),
-    }
-}
-
-pub fn s_bytes(data: &mut [u8], sbox: &CacheWarmup64, cfg: Config) -> Result<(), Errors> {
-    let pool = get_thread_pool(cfg.thread_strategy.get_cpu_count(), cfg.stack_size);
-    if cfg.hardware.warmup_cache {
-        sbox.pre_sbox_warmup();
-    }
-
-    match cfg.subtle_sbox {
-        true => Ok(pool.install(|| {
-            data.par_iter_mut()
-                .for_each(|b| *b = constant_time_sbox_lookup(&sbox.sbox, *b));
-        })),
-        false => Ok(pool.install(|| {
-            data.par_iter_mut()
-                .for_each(|b| *b = sbox.cache_time_lookup(*b));
-        })),
-    }
-}
-
 pub fn generate_keystream_32(nonce: &[u8], block_counter: u64, chunk_idx: usize) -> [u8; 32] {
     let mut keystream = [0u8; 32];
 
@@ -0,0 +1,291 @@
+//! Fused, cache-tiled execution of the block cipher layers.
+//! - Phase A runs the key layers (RXA, S-box, Galois field, ShiftRows) over the whole buffer,
+//!   one tile at a time, so each tile goes through every layer while it is still in L2.
+//! - Phase B runs every round the same way, tiled within each `ROUND_CHUNK_SIZE` chunk because
+//!   the round Galois field counter restarts per chunk.
+//! - Tiles are multiples of 192 bytes, the least common multiple of the 3 and 4-byte Galois field
+//!   columns, 16-byte ShiftRows blocks and 64-byte key positions, so the output is byte-identical
+//!   to running the layers one after another over the whole buffer.
+//! - The CTR layer chains every block to the previous one and stays a separate pass.
+
+use std::hint::black_box;
+
+use rayon::prelude::*;
+use sha3::{Digest, Sha3_512};
+use zeroize::Zeroize;
+
+#[cfg(target_arch = "x86_64")]
+use crate::engine::{
+    engine::avx2_enabled,
+    simd::{avx2_inverse_shift_rows, avx2_shift_rows},
+};
+use crate::{
+    Config, Errors, GaloisFieldType,
+    engine::{
+        cache_warmup::{CacheWarmup, CacheWarmup64},
+        engine::{
+            GaloisField, ROTATIONS, aes_column_stream, constant_time_sbox_lookup, dummy_noise,
+            inverse_shift_rows_block, key_lookup, shift_rows_block, thread_pool,
+            triangle_column_stream,
+        },
+    },
+};
+
+/// Bytes processed through every layer of a phase before moving on, a multiple of 192.
+pub const TILE_SIZE: usize = 192 * 1024;
+/// Rounds run over the data in chunks of this size.
+pub const ROUND_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Expanded key streams of one operation, zeroized on drop.
+struct KeyStreams {
+    key: [u8; 64],
+    rounds: Vec<[u8; 64]>,
+}
+
+impl KeyStreams {
+    fn new(pwd: &CacheWarmup64, config: &Config) -> Self {
+        let rounds = (1..=config.rounds)
+            .map(|i| {
+                let mut hash = Sha3_512::new();
+                hash.update(&pwd.key[..std::cmp::min(i * 32, 64)]);
+                let round_key = CacheWarmup64 {
+                    key: hash.finalize().into(),
+                    sbox: [0u8; 256],
+                    inv_sbox: [0u8; 256],
+                };
+                if config.hardware.warmup_cache {
+                    round_key.warm_cache();
+                }
+
+                expand_key(&round_key, config)
+            })
+            .collect();
+
+        Self {
+            key: expand_key(pwd, config),
+            rounds,
+        }
+    }
+
+    /// Key stream of round `i`, counted from 1.
+    fn round(&self, i: usize) -> &[u8; 64] {
+        &self.rounds[i - 1]
+    }
+}
+
+impl Drop for KeyStreams {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.rounds.iter_mut().for_each(|key| key.zeroize());
+    }
+}
+
+/// Key byte of every position, looked up once instead of once per data byte.
+fn expand_key(key: &CacheWarmup64, config: &Config) -> [u8; 64] {
+    std::array::from_fn(|i| key_lookup(key, i as u8, config))
+}
+
+/// Runs the key layers and every round over `data`, like the layered engine up to the CTR layer.
+///
+/// # Errors
+/// Returns `Errors::GaloisFieldError` for empty data.
+pub fn fused_encrypt(
+    data: &mut [u8],
+    pwd: &CacheWarmup64,
+    gf: &GaloisField,
+    nonce: &[u8],
+    config: &Config,
+) -> Result<(), Errors> {
+    if data.is_empty() {
+        return Err(Errors::GaloisFieldError("Empty Data".to_string()));
+    }
+
+    let keys = KeyStreams::new(pwd, config);
+    let pool = thread_pool(config);
+
+    pool.install(|| {
+        gf_noise(config);
+        data.par_chunks_mut(TILE_SIZE)
+            .enumerate()
+            .for_each(|(i, tile)| {
+                rxa_forward(tile, &keys.key);
+                substitute(tile, pwd, false, config);
+                rxa_forward(tile, &keys.key);
+                mix_columns(tile, i * TILE_SIZE, gf, nonce, config);
+                shift_rows(tile, config);
+                substitute(tile, pwd, false, config);
+            });
+
+        data.par_chunks_mut(ROUND_CHUNK_SIZE).for_each(|chunk| {
+            (1..=config.rounds)
+                .filter(|i| config.multi_round_galois_field || *i == 1)
+                .for_each(|_| gf_noise(config));
+
+            chunk
+                .par_chunks_mut(TILE_SIZE)
+                .enumerate()
+                .for_each(|(i, tile)| {
+                    for round in 1..=config.rounds {
+                        rxa_forward(tile, keys.round(round));
+                        if config.multi_round_galois_field || round == 1 {
+                            mix_columns(tile, i * TILE_SIZE, gf, nonce, config);
+                        }
+                    }
+                });
+        });
+    });
+
+    Ok(())
+}
+
+/// Reverses `fused_encrypt`, `data` must already have the CTR layer removed.
+///
+/// # Errors
+/// Returns `Errors::GaloisFieldError` for empty data.
+pub fn fused_decrypt(
+    data: &mut [u8],
+    pwd: &CacheWarmup64,
+    gf: &GaloisField,
+    nonce: &[u8],
+    config: &Config,
+) -> Result<(), Errors> {
+    if data.is_empty() {
+        return Err(Errors::GaloisFieldError("Empty Data".to_string()));
+    }
+
+    let keys = KeyStreams::new(pwd, config);
+    let pool = thread_pool(config);
+
+    pool.install(|| {
+        data.par_chunks_mut(ROUND_CHUNK_SIZE).for_each(|chunk| {
+            (1..=config.rounds)
+                .filter(|i| config.multi_round_galois_field || *i == 1)
+                .for_each(|_| gf_noise(config));
+
+            chunk
+                .par_chunks_mut(TILE_SIZE)
+                .enumerate()
+                .for_each(|(i, tile)| {
+                    for round in (1..=config.rounds).rev() {
+                        if config.multi_round_galois_field || round == 1 {
+                            mix_columns(tile, i * TILE_SIZE, gf, nonce, config);
+                        }
+                        rxa_inverse(tile, keys.round(round));
+                    }
+                });
+        });
+
+        gf_noise(config);
+        data.par_chunks_mut(TILE_SIZE)
+            .enumerate()
+            .for_each(|(i, tile)| {
+                substitute(tile, pwd, true, config);
+                inverse_shift_rows(tile, config);
+                mix_columns(tile, i * TILE_SIZE, gf, nonce, config);
+                rxa_inverse(tile, &keys.key);
+                substitute(tile, pwd, true, config);
+                rxa_inverse(tile, &keys.key);
+            });
+    });
+
+    Ok(())
+}
+
+/// Rotate, XOR and add in one pass, `tile` must start at a multiple of 64.
+fn rxa_forward(tile: &mut [u8], key: &[u8; 64]) {
+    for block in tile.chunks_mut(64) {
+        for (i, b) in block.iter_mut().enumerate() {
+            *b = (b.rotate_left(ROTATIONS[i % 8]) ^ key[i]).wrapping_add(key[i]);
+        }
+    }
+}
+
+fn rxa_inverse(tile: &mut [u8], key: &[u8; 64]) {
+    for block in tile.chunks_mut(64) {
+        for (i, b) in block.iter_mut().enumerate() {
+            *b = (b.wrapping_sub(key[i]) ^ key[i]).rotate_right(ROTATIONS[i % 8]);
+        }
+    }
+}
+
+fn substitute(tile: &mut [u8], pwd: &CacheWarmup64, inverse: bool, config: &Config) {
+    if config.hardware.warmup_cache {
+        pwd.pre_sbox_warmup();
+    }
+
+    match (config.subtle_sbox, inverse) {
+        (true, false) => tile
+            .iter_mut()
+            .for_each(|b| *b = constant_time_sbox_lookup(&pwd.sbox, *b)),
+        (true, true) => tile
+            .iter_mut()
+            .for_each(|b| *b = constant_time_sbox_lookup(&pwd.inv_sbox, *b)),
+        (false, false) => tile.iter_mut().for_each(|b| *b = pwd.cache_time_lookup(*b)),
+        (false, true) => tile
+            .iter_mut()
+            .for_each(|b| *b = pwd.cache_inverse_lookup(*b)),
+    }
+}
+
+/// Galois field layer of a tile starting `offset` bytes into the buffer or round chunk.
+fn mix_columns(tile: &mut [u8], offset: usize, gf: &GaloisField, nonce: &[u8], config: &Config) {
+    gf.warm_cache();
+
+    match config.gf_type {
+        GaloisFieldType::Triangular => triangle_columns(tile, offset / 3, gf, nonce),
+        GaloisFieldType::AES => aes_columns(tile, offset / 4, gf, nonce),
+        GaloisFieldType::Hybrid => {
+            aes_columns(tile, offset / 4, gf, nonce);
+            triangle_columns(tile, offset / 3, gf, nonce);
+        }
+    }
+}
+
+fn aes_columns(tile: &mut [u8], first_column: usize, gf: &GaloisField, nonce: &[u8]) {
+    for (idx, column) in tile.chunks_exact_mut(4).enumerate() {
+        let key_stream = aes_column_stream(gf, nonce, first_column + idx);
+        for (byte, key) in column.iter_mut().zip(key_stream) {
+            *byte ^= black_box(key);
+        }
+    }
+}
+
+fn triangle_columns(tile: &mut [u8], first_column: usize, gf: &GaloisField, nonce: &[u8]) {
+    for (idx, column) in tile.chunks_exact_mut(3).enumerate() {
+        let key_stream = triangle_column_stream(gf, nonce, first_column + idx);
+        for (byte, key) in column.iter_mut().zip(key_stream) {
+            *byte ^= black_box(key);
+        }
+    }
+}
+
+/// Dummy data of every Galois field function a layer runs.
+fn gf_noise(config: &Config) {
+    match config.gf_type {
+        GaloisFieldType::Hybrid => {
+            dummy_noise(config);
+            dummy_noise(config);
+        }
+        _ => dummy_noise(config),
+    }
+}
+
+fn shift_rows(tile: &mut [u8], config: &Config) {
+    #[cfg(target_arch = "x86_64")]
+    if avx2_enabled(config) {
+        return unsafe { avx2_shift_rows(tile) };
+    }
+
+    let _ = config;
+    tile.chunks_exact_mut(16).for_each(shift_rows_block);
+}
+
+fn inverse_shift_rows(tile: &mut [u8], config: &Config) {
+    #[cfg(target_arch = "x86_64")]
+    if avx2_enabled(config) {
+        return unsafe { avx2_inverse_shift_rows(tile) };
+    }
+
+    let _ = config;
+    tile.chunks_exact_mut(16).for_each(inverse_shift_rows_block);
+}
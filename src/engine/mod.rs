@@ -1,3 +1,5 @@
 pub mod cache_warmup;
 pub mod engine;
+pub mod fused;
+#[cfg(target_arch = "x86_64")]
 pub mod simd;
@@ -145,3 +145,33 @@ pub unsafe fn avx2_sub_inplace(data: &mut [u8], key: &CacheWarmup64, config: &Co
             .for_each(|(i, b)| *b = b.wrapping_sub(key_lookup(key, (start + i) as u8, config)));
     }
 }
+
+/// Source index of every byte of a 16-byte block after ShiftRows.
+const SHIFT_ROWS_MASK: [u8; 16] = [0, 5, 10, 15, 4, 9, 14, 3, 8, 13, 2, 7, 12, 1, 6, 11];
+/// Source index of every byte of a 16-byte block after inverse ShiftRows.
+const INV_SHIFT_ROWS_MASK: [u8; 16] = [0, 13, 10, 7, 4, 1, 14, 11, 8, 5, 2, 15, 12, 9, 6, 3];
+
+#[inline(always)]
+unsafe fn shuffle_block(chunk: &mut [u8], mask: &[u8; 16]) {
+    unsafe {
+        let mask = _mm_loadu_si128(mask.as_ptr() as *const __m128i);
+        let shuffled = _mm_shuffle_epi8(_mm_loadu_si128(chunk.as_ptr() as *const __m128i), mask);
+        _mm_storeu_si128(chunk.as_mut_ptr() as *mut __m128i, shuffled);
+    }
+}
+
+/// ShiftRows of every 16-byte block, single-threaded since tiles are already processed in parallel.
+#[target_feature(enable = "avx2")]
+pub unsafe fn avx2_shift_rows(data: &mut [u8]) {
+    for chunk in data.chunks_exact_mut(16) {
+        unsafe { shuffle_block(chunk, &SHIFT_ROWS_MASK) };
+    }
+}
+
+/// Inverse of `avx2_shift_rows`.
+#[target_feature(enable = "avx2")]
+pub unsafe fn avx2_inverse_shift_rows(data: &mut [u8]) {
+    for chunk in data.chunks_exact_mut(16) {
+        unsafe { shuffle_block(chunk, &INV_SHIFT_ROWS_MASK) };
+    }
+}
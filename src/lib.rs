@@ -115,10 +115,10 @@ let encrypted = CrystalystBuilder::new()
     .encrypt()
     .expect("encryption failed");
 
+// Config, nonce and salt are read back from the ciphertext header
 let decrypted = CrystalystBuilder::new()
     .data(&encrypted)
     .password("super_secret_password")
-    .config(config)
     .decrypt()
     .expect("decryption failed");
 
@@ -129,7 +129,7 @@ assert_eq!(decrypted, b"Hello, world!");
 
 ## Key Features
 - **Testing**: Local entropy/avalanche/bit balance testing modules included.
-- **Recovery Key**: Generates recovery key based on your main Password and Nonce.
+- **Recovery Key**: Returns a per-ciphertext recovery phrase with checksum words from `encrypt`, decrypt with `decrypt_with_recovery_key`.
 - **Counter Mode**: Securely manages encryption and decryption using a counter mode.
 - **SIMD Support**: Processing through single instruction but multiple data. (Performance boost)
 - **TPM Operations**: Securely manages cryptographic operations using Trusted Platform Module (TPM).
@@ -138,9 +138,25 @@ assert_eq!(decrypted, b"Hello, world!");
 - **Salt Support**: Cryptographic salt generation using `Salt::new()` to prevent rainbow table attacks.
 - **Infinite Rounds**: User-defined encryption round count.
 - **Wrap-All Support**: Seamlessly wraps salt, nonce, version, etc. into final output.
+- **Self-Describing Ciphertext**: Versioned, MAC-covered header carries the configuration needed for decryption.
 - **MAC with SHA3-512**: Strong integrity validation and quantum resistance.
+- **Stream Files**: `.cryst` files with per-chunk authentication tags, salted key derivation and a config header, decrypt with only the password.
+- **Streaming I/O**: `std::io` and (with the `async` feature) `futures-io` reader/writer adapters with bounded memory.
+- **Rekey**: Changes the password of ciphertexts and stream files with a fresh nonce and salt, without handing out the plaintext.
+- **Key Slots**: LUKS-style containers where password, recovery phrase and Kyber slots each unlock one data key, slots can be added or revoked without re-encrypting.
+- **Kyber Sealing**: `kem::seal`/`kem::open` encrypt to one or more Kyber public keys through the block cipher (`kyber` feature).
+- **Kyber Key Files**: Binary and armored (`kyber_shared` plus a Kyber level feature) key formats, private keys encrypted with the block cipher and Argon2.
+- **Kyber Levels**: `kyber` uses Kyber-512, `kyber768` or `kyber1024` select another parameter set for the whole build.
+- **Kyber AKE**: Mutually authenticated key exchange, per-handshake state lives in `AkeSession` so one identity serves many peers.
+- **Secure Channel**: `CrystalystChannel` runs the UAKE or AKE handshake over any `Read + Write` transport, then exchanges framed records with per-direction keys and replay protection.
+- **Trust Store**: SHA3 key fingerprints (hex or words), TOFU pinning, explicit trust and revocation, checked by `client_init_verified`.
+- **Key Cache**: `KeyCache::global()` with configurable capacity, LRU eviction and `purge()`, a capacity of 0 disables it.
+- **Portable Engine**: SIMD paths are x86_64-only behind target cfgs, other targets (e.g. aarch64) build with the scalar implementation.
+- **Reference Implementation**: Scalar single-threaded `reference_encrypt`/`reference_decrypt`, `differential_check` proves every backend and thread strategy produces the same ciphertext.
+- **Fused Engine**: Block and stream layers run tile by tile (192 KiB) while the data is still in L2, with key streams expanded once per operation; output is byte-identical to the reference.
+- **Associated Data**: Bind ciphertexts to their context (record id, file path...) with `.associated_data()`.
 - **Benchmark Support**: Time encryption/decryption operations with `.benchmark()`.
-- **Secure Key Derivation**: Argon2 for password hashing.
+- **Secure Key Derivation**: Argon2 for password hashing, cost scaled by profile or calibrated via `Argon2Params::calibrate()`.
 - **Dynamic S-boxes**: Based on password, nonce, or both.
 - **Finite Field Arithmetic**: Galois Field operations similar to AES MixColumns and you can use AES MixColumns.
 - **Dummy Data**:
@@ -201,7 +217,7 @@ let config = Config::default().set_hardware(Hardware::new().set_hardware_hashing
 ```
 
 ### Custom Configuration
-- 🚧 If you forget your configuration, you won't be able to decrypt the data. (Especially important if you changed round count, Key Length, or polynomial.)
+- Every decryption-relevant setting (rounds, Galois field type & polynomial, CTR layer, Argon2 type...) is stored in the MAC-covered ciphertext header, decryption does not need the original configuration.
 ```rust
 use crystalyst::{CrystalystBuilder, Config, DeviceList, SboxTypes, IrreduciblePoly};
 
@@ -252,7 +268,10 @@ use hmac::Hmac;
 use hmac::Mac;
 use rand::thread_rng;
 use secrecy::{ExposeSecret, SecretBox};
-use sha3::Sha3_512;
+use sha3::{Digest, Sha3_256, Sha3_512};
+use std::thread;
+#[cfg(feature = "key_derivation")]
+use std::time::{Duration, Instant};
 #[cfg(feature = "key_derivation")]
 use subtle::ConstantTimeLess;
 use sysinfo::System;
@@ -263,6 +282,13 @@ use tss_esapi::tcti_ldr::DeviceConfig;
 use zeroize::Zeroize;
 
 use crate::rng_utils::nonce::NonceData;
+
+#[cfg(any(
+    all(feature = "kyber512", feature = "kyber768"),
+    all(feature = "kyber512", feature = "kyber1024"),
+    all(feature = "kyber768", feature = "kyber1024"),
+))]
+compile_error!("enable only one of the `kyber512`, `kyber768` and `kyber1024` features");
 #[cfg(feature = "key_derivation")]
 use crate::rng_utils::salt::Salt;
 #[cfg(feature = "machine_rng")]
@@ -321,6 +347,45 @@ impl Drop for RoundKeyBuffer {
     }
 }
 
+/// Recovery phrase for a single ciphertext, returned by encryption when `Utils::recovery_key(true)` is set.
+/// - The phrase is zeroized on drop and never printed by the library.
+/// - Anyone holding it can decrypt the ciphertext without the password, store it offline.
+/// - The last two words are a checksum, so transcription errors are caught before decryption.
+/// - Words are case-insensitive and may be shortened to their first 3 letters.
+pub struct RecoveryKey(SecretBox<str>);
+
+impl RecoveryKey {
+    pub fn new(phrase: String) -> Self {
+        RecoveryKey(SecretBox::new(phrase.into_boxed_str()))
+    }
+
+    /// Returns the recovery phrase.
+    pub fn expose_secret(&self) -> &str {
+        self.0.expose_secret()
+    }
+
+    /// Checks the words and checksum of a written-down phrase, no ciphertext is needed.
+    /// - A single mistyped word is accepted when the checksum identifies the correction.
+    /// - Returns `Errors::InvalidRecoveryKey` with suggested corrections for unknown words.
+    pub fn verify(phrase: &str) -> Result<(), Errors> {
+        decode_recovery_phrase(phrase).map(|mut indices| indices.zeroize())
+    }
+
+    /// Returns up to 3 wordlist entries close to `word`, closest first.
+    pub fn suggest(word: &str) -> Vec<&'static str> {
+        recovery_word_suggestions(word)
+            .into_iter()
+            .map(|index| RECOVERY_WORDS[index as usize])
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for RecoveryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RecoveryKey([REDACTED])")
+    }
+}
+
 static VERSION: &[u8] = b"CRYSTALYST-version:0x9";
 
 /// Represents different types of errors that can occur during encryption or decryption.
@@ -379,6 +444,20 @@ pub enum Errors {
     NotBackwardCompatible,
     #[error("Kyber Error: {0}")]
     KyberError(String),
+    #[error("Invalid Header: {0}")]
+    InvalidHeader(String),
+    #[error("I/O Error: {0}")]
+    IoError(String),
+    #[error("Invalid Recovery Key: {0}")]
+    InvalidRecoveryKey(String),
+    #[error("Key Slot Error: {0}")]
+    KeySlotError(String),
+    #[error("Channel Error: {0}")]
+    ChannelError(String),
+    #[error("Untrusted Key: {0}")]
+    UntrustedKey(String),
+    #[error("Backend Mismatch: {0}")]
+    BackendMismatch(String),
 }
 
 /// Represents different types of irreducible polynomials that can be used for encryption and decryption.
@@ -427,6 +506,115 @@ pub enum Argon2Type {
     Argon2id,
 }
 
+#[cfg(feature = "key_derivation")]
+/// Argon2 cost parameters used for password hashing.
+/// - Stored in the ciphertext header, decryption always uses the parameters the data was encrypted with.
+/// - Use `Argon2Params::calibrate()` to pick parameters for a target time on the current machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Params {
+    /// Memory cost in KiB
+    pub memory_cost: u32,
+    /// Number of passes over the memory
+    pub iterations: u32,
+    /// Degree of parallelism
+    pub lanes: u32,
+}
+
+#[cfg(feature = "key_derivation")]
+impl Argon2Params {
+    /// Highest memory cost accepted from a ciphertext header (4 GiB).
+    pub const MAX_MEMORY_COST: u32 = 4 * 1024 * 1024;
+    /// Highest iteration count accepted from a ciphertext header.
+    pub const MAX_ITERATIONS: u32 = 256;
+    /// Highest lane count accepted from a ciphertext header.
+    pub const MAX_LANES: u32 = 64;
+
+    /// 8 MiB, 1 iteration, 1 lane.
+    pub const FAST: Argon2Params = Argon2Params::new(8 * 1024, 1, 1);
+    /// Argon2 crate defaults: 19 MiB, 2 iterations, 1 lane.
+    pub const DEFAULT: Argon2Params = Argon2Params::new(
+        Params::DEFAULT_M_COST,
+        Params::DEFAULT_T_COST,
+        Params::DEFAULT_P_COST,
+    );
+    /// 64 MiB, 3 iterations, 4 lanes.
+    pub const SECURE: Argon2Params = Argon2Params::new(64 * 1024, 3, 4);
+    /// 256 MiB, 3 iterations, 4 lanes.
+    pub const MAX: Argon2Params = Argon2Params::new(256 * 1024, 3, 4);
+    /// 512 MiB, 4 iterations, 4 lanes.
+    pub const FORTRESS: Argon2Params = Argon2Params::new(512 * 1024, 4, 4);
+    /// 1 GiB, 4 iterations, 4 lanes.
+    pub const EXTREME: Argon2Params = Argon2Params::new(1024 * 1024, 4, 4);
+
+    pub const fn new(memory_cost: u32, iterations: u32, lanes: u32) -> Self {
+        Self {
+            memory_cost,
+            iterations,
+            lanes,
+        }
+    }
+
+    /// Measures Argon2 on this machine and picks parameters that take roughly `target` per derivation.
+    ///
+    /// Memory cost is doubled (starting from the default) until a single iteration takes half of the target,
+    /// then scaled to fill the target. If memory hits its cap (1 GiB or a quarter of the available memory),
+    /// iterations are added to fill the rest, up to `MAX_ITERATIONS`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use crystalyst_rs::{Argon2Params, Argon2Type, Config};
+    ///
+    /// # fn main() -> Result<(), crystalyst_rs::Errors> {
+    /// let params = Argon2Params::calibrate(Argon2Type::Argon2id, Duration::from_millis(500), 1)?;
+    /// let config = Config::DEFAULT.argon2_params(params);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn calibrate(
+        argon2_type: Argon2Type,
+        target: Duration,
+        lanes: u32,
+    ) -> Result<Self, Errors> {
+        let lanes = lanes.max(1);
+
+        let mut sys = System::new();
+        sys.refresh_memory();
+        let memory_limit = (sys.available_memory() / 1024 / 4).clamp(
+            Params::DEFAULT_M_COST as u64,
+            Self::EXTREME.memory_cost as u64,
+        ) as u32;
+
+        let measure = |params: Argon2Params| -> Result<Duration, Errors> {
+            let mut out = [0u8; 64];
+            let start = Instant::now();
+            argon2_instance(argon2_type, params)?
+                .hash_password_into(b"CRYSTALYST-calibration", &[0u8; 32], &mut out)
+                .map_err(|e| Errors::Argon2Failed(e.to_string()))?;
+            Ok(start.elapsed())
+        };
+
+        let mut params = Self::new(Params::DEFAULT_M_COST, 1, lanes);
+        let mut elapsed = measure(params)?;
+
+        while elapsed < target / 2 && params.memory_cost * 2 <= memory_limit {
+            params.memory_cost *= 2;
+            elapsed = measure(params)?;
+        }
+
+        let scale = target.as_secs_f64() / elapsed.as_secs_f64().max(f64::EPSILON);
+        let memory_cost =
+            ((params.memory_cost as f64 * scale) as u32).min(memory_limit) / 1024 * 1024;
+        let memory_cost = memory_cost.max(Params::DEFAULT_M_COST);
+        let remaining = scale * params.memory_cost as f64 / memory_cost as f64;
+
+        params.memory_cost = memory_cost;
+        params.iterations = (remaining.round().max(1.0) as u32).min(Self::MAX_ITERATIONS);
+
+        Ok(params)
+    }
+}
+
 /// Thread strategy for the encryption and decryption process.
 /// # ⚠️ WARNING: If SIMD (AVX2) enabled encryption will always use full threads.
 /// - `AutoThread`: Automatically determine the number of threads to use.
@@ -466,6 +654,7 @@ pub struct Hardware {
     /// Using TPM for hashing
     pub hardware_hashing: bool,
     /// Enable AVX2 usage
+    /// - Ignored on non-x86_64 targets, they always use the portable implementation.
     pub enable_avx2: bool,
     /// Warm up Cache
     /// # PROVIDING RESISTANCE AGAINST SIDE-CHANNEL ATTACKS
@@ -606,6 +795,11 @@ pub struct Config {
     /// - Performing planned migration with data conversion
     /// - You have cryptographic expertise
     pub argon2_type: Argon2Type,
+
+    #[cfg(feature = "key_derivation")]
+    /// Argon2 memory cost, iterations and lanes.
+    /// Stored in the ciphertext, decryption picks them up automatically.
+    pub argon2_params: Argon2Params,
 }
 
 impl ThreadStrategy {
@@ -613,38 +807,41 @@ impl ThreadStrategy {
         Self::Custom(num_threads)
     }
 
+    /// Returns the number of threads this strategy runs with, at least 1.
+    /// - Based on the machine's available parallelism, so the result is the same inside and
+    ///   outside of a thread pool.
     pub fn get_cpu_count(&self) -> usize {
-        match self {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+        let count = match self {
             Self::AutoThread => {
                 let mut sys = System::new();
                 sys.refresh_cpu_usage();
                 let cpu_usage = sys.global_cpu_usage();
 
                 match cpu_usage as u32 {
-                    0..45 => rayon::current_num_threads(),
-                    45..65 => {
-                        (rayon::current_num_threads() / 2) + (rayon::current_num_threads() / 4)
-                    }
-                    65..80 => rayon::current_num_threads() / 2,
-                    80..99 => rayon::current_num_threads() / 4,
+                    0..45 => threads,
+                    45..65 => (threads / 2) + (threads / 4),
+                    65..80 => threads / 2,
+                    80..99 => threads / 4,
                     _ => 1,
                 }
             }
-            Self::FullThread => rayon::current_num_threads(),
-            Self::LowThread => rayon::current_num_threads() / 2,
-            Self::BulkOperations => {
-                (rayon::current_num_threads() / 2) + (rayon::current_num_threads() / 4)
-            }
+            Self::FullThread => threads,
+            Self::LowThread => threads / 2,
+            Self::BulkOperations => (threads / 2) + (threads / 4),
             Self::SingleThread => 1,
             Self::Gaming => {
-                if rayon::current_num_threads() > 6 {
-                    rayon::current_num_threads() / 4
+                if threads > 6 {
+                    threads / 4
                 } else {
-                    rayon::current_num_threads() / 2
+                    threads / 2
                 }
             }
             Self::Custom(num_threads) => *num_threads,
-        }
+        };
+
+        count.max(1)
     }
 }
 
@@ -668,6 +865,8 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::DEFAULT,
         hardware: Hardware::DEFAULT,
     };
 
@@ -696,6 +895,8 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::DEFAULT,
         hardware: Hardware::DEFAULT,
     };
 
@@ -718,6 +919,8 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::FAST,
         hardware: Hardware::DEFAULT,
     };
 
@@ -740,6 +943,8 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::DEFAULT,
         hardware: Hardware::DEFAULT,
     };
 
@@ -762,6 +967,8 @@ impl Config {
         zeroize: true,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::SECURE,
         hardware: Hardware::DEFAULT,
     };
 
@@ -787,6 +994,8 @@ impl Config {
         zeroize: true,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::SECURE,
         hardware: Hardware::DEFAULT,
     };
 
@@ -810,6 +1019,8 @@ impl Config {
         zeroize: true,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::MAX,
         hardware: Hardware::DEFAULT,
     };
 
@@ -833,6 +1044,8 @@ impl Config {
         zeroize: true,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::FORTRESS,
         hardware: Hardware::DEFAULT,
     };
 
@@ -856,6 +1069,8 @@ impl Config {
         zeroize: true,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::EXTREME,
         hardware: Hardware::DEFAULT,
     };
 
@@ -878,6 +1093,8 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::DEFAULT,
         hardware: Hardware::DEFAULT,
     };
 
@@ -902,6 +1119,8 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::FAST,
         hardware: Hardware::DEFAULT,
     };
 
@@ -929,11 +1148,13 @@ impl Config {
         zeroize: false,
         #[cfg(feature = "key_derivation")]
         argon2_type: Argon2Type::Argon2id,
+        #[cfg(feature = "key_derivation")]
+        argon2_params: Argon2Params::FAST,
         hardware: Hardware::DEFAULT,
     };
 
     /// Sets the number of threads to use for encryption and decryption.
-    /// - Not recommended changing the number of threads after initialization.
+    /// - Every thread count and stack size gets its own pool, built on first use and reused afterwards.
     pub fn set_thread(mut self, strategy: ThreadStrategy) -> Self {
         self.thread_strategy = strategy;
         self
@@ -1092,6 +1313,23 @@ impl Config {
         self.argon2_type = argon2_type;
         self
     }
+
+    #[cfg(feature = "key_derivation")]
+    /// Sets the Argon2 memory cost, iterations and lanes.
+    /// - Stored in the ciphertext, changing it does not affect existing data.
+    pub fn argon2_params(mut self, argon2_params: Argon2Params) -> Self {
+        self.argon2_params = argon2_params;
+        self
+    }
+
+    #[cfg(feature = "key_derivation")]
+    /// Replaces the Argon2 parameters with ones calibrated for `target` on this machine.
+    /// - See `Argon2Params::calibrate()`.
+    pub fn calibrate_argon2(mut self, target: Duration) -> Result<Self, Errors> {
+        self.argon2_params =
+            Argon2Params::calibrate(self.argon2_type, target, self.argon2_params.lanes)?;
+        Ok(self)
+    }
 }
 
 /// Using TPM for secure storage and nonce generation.
@@ -1157,6 +1395,28 @@ impl TpmModule {
 
 // -----------------------------------------------------
 
+#[cfg(feature = "key_derivation")]
+fn argon2_instance(
+    argon2_type: Argon2Type,
+    argon2_params: Argon2Params,
+) -> Result<Argon2<'static>, Errors> {
+    let params = Params::new(
+        argon2_params.memory_cost,
+        argon2_params.iterations,
+        argon2_params.lanes,
+        Some(64),
+    )
+    .map_err(|e| Errors::Argon2Failed(e.to_string()))?;
+
+    let algorithm = match argon2_type {
+        Argon2Type::Argon2d => Algorithm::Argon2d,
+        Argon2Type::Argon2i => Algorithm::Argon2i,
+        Argon2Type::Argon2id => Algorithm::Argon2id,
+    };
+
+    Ok(Argon2::new(algorithm, Version::V0x13, params))
+}
+
 #[cfg(feature = "key_derivation")]
 fn derive_password_key(
     pwd: &[u8],
@@ -1175,11 +1435,7 @@ fn derive_password_key(
         salt = custom_salt.as_bytes().to_vec();
     }
 
-    let argon = match config.argon2_type {
-        Argon2Type::Argon2d => Argon2::new(Algorithm::Argon2d, Version::V0x13, Params::DEFAULT),
-        Argon2Type::Argon2i => Argon2::new(Algorithm::Argon2i, Version::V0x13, Params::DEFAULT),
-        Argon2Type::Argon2id => Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::DEFAULT),
-    };
+    let argon = argon2_instance(config.argon2_type, config.argon2_params)?;
 
     let mut out = vec![0u8; 64];
     argon
@@ -1189,6 +1445,14 @@ fn derive_password_key(
     Ok(out)
 }
 
+/// Disables key derivation, for keys that are random and need no password hardening.
+fn without_key_derivation(config: Config) -> Config {
+    #[cfg(feature = "key_derivation")]
+    let config = config.key_derivation(false);
+
+    config
+}
+
 // -----------------------------------------------------
 
 fn secure_zeroize(data: &mut [u8], config: &Config) {
@@ -1213,303 +1477,213 @@ fn calculate_hmac(key: &[u8], message: &[u8]) -> Result<Vec<u8>, Errors> {
     Ok(mac.finalize().into_bytes().to_vec())
 }
 
-fn generate_full_wordlist() -> Vec<String> {
-    vec![
-        "APPLE",
-        "BANANA",
-        "CHERRY",
-        "DATE",
-        "ELDERBERRY",
-        "ALPHA",
-        "OMEGA",
-        "UPPER",
-        "STAIR",
-        "LOWER",
-        "99",
-        "23",
-        "83",
-        "61",
-        "TABLE",
-        "CHAIR",
-        "MEMORIES",
-        "GAME",
-        "VIDEO",
-        "FOOTBALL",
-        "BASKETBALL",
-        "CRICKET",
-        "BALL",
-        "COMPUTER",
-        "CPU",
-        "GPU",
-        "ALGORITHM",
-        "BIT",
-        "BYTE",
-        "CODE",
-        "LOGIC",
-        "RUST",
-        "PYTHON",
-        "JAVA",
-        "SWIFT",
-        "KERNEL",
-        "PIXEL",
-        "BLOCK",
-        "CHAIN",
-        "HASH",
-        "MINT",
-        "SNOW",
-        "RAVEN",
-        "FALCON",
-        "NOVA",
-        "JUNO",
-        "TANGO",
-        "ECHO",
-        "LIMA",
-        "ZULU",
-        "DELTA",
-        "VICTOR",
-        "WHISKEY",
-        "XRAY",
-        "YANKIE",
-        "ZETA",
-        "SIGMA",
-        "THETA",
-        "EPSILON",
-        "GAMMA",
-        "NEON",
-        "QUARK",
-        "FLUX",
-        "MOON",
-        "ORBIT",
-        "SUN",
-        "SOLAR",
-        "MARS",
-        "VENUS",
-        "PLUTO",
-        "EARTH",
-        "SATURN",
-        "URANUS",
-        "BINARY",
-        "HEX",
-        "DECIMAL",
-        "CIPHER",
-        "MATRIX",
-        "NODE",
-        "JUPITER",
-        "NEPTUNE",
-        "MERCURY",
-        "COMET",
-        "STAR",
-        "GALAXY",
-        "NEBULA",
-        "COSMIC",
-        "QUANTUM",
-        "PARTICLE",
-        "ATOM",
-        "ELECTRON",
-        "PROTON",
-        "NEUTRON",
-        "WAVE",
-        "ENERGY",
-        "LASER",
-        "PLASMA",
-        "ROCKET",
-        "SHUTTLE",
-        "STATION",
-        "SATELLITE",
-        "ROVER",
-        "LANDER",
-        "MISSION",
-        "LAUNCH",
-        "CLOUD",
-        "DATABASE",
-        "SERVER",
-        "NETWORK",
-        "ROUTER",
-        "SWITCH",
-        "FIREWALL",
-        "PROTOCOL",
-        "WIFI",
-        "BLUETOOTH",
-        "ETHERNET",
-        "MODEM",
-        "BROWSER",
-        "WEBSITE",
-        "INTERNET",
-        "DOMAIN",
-        "EMAIL",
-        "PASSWORD",
-        "USERNAME",
-        "LOGIN",
-        "ACCOUNT",
-        "PROFILE",
-        "AVATAR",
-        "DIGITAL",
-        "VIRTUAL",
-        "REALITY",
-        "AUGMENT",
-        "NEURAL",
-        "CRYPTO",
-        "TOKEN",
-        "WALLET",
-        "SMART",
-        "CONTRACT",
-        "TRANSACTION",
-        "LEDGER",
-        "MINE",
-        "VERIFY",
-        "VALIDATE",
-        "ENCRYPT",
-        "DECRYPT",
-        "SECURE",
-        "KEY",
-        "PRIVATE",
-        "PUBLIC",
-        "OCEAN",
-        "RIVER",
-        "LAKE",
-        "MOUNTAIN",
-        "FOREST",
-        "DESERT",
-        "ARCTIC",
-        "TROPICS",
-        "PRAIRIE",
-        "CANYON",
-        "VALLEY",
-        "REEF",
-        "ISLAND",
-        "BEACH",
-        "COAST",
-        "VOLCANO",
-        "GLACIER",
-        "STORM",
-        "THUNDER",
-        "LIGHTNING",
-        "RAINBOW",
-        "SUNRISE",
-        "SUNSET",
-        "HORIZON",
-        "ZENITH",
-        "NADIR",
-        "DRAGON",
-        "PHOENIX",
-        "GRIFFIN",
-        "UNICORN",
-        "KRAKEN",
-        "HYDRA",
-        "SPHINX",
-        "PEGASUS",
-        "MINOTAUR",
-        "CENTAUR",
-        "SIREN",
-        "CHIMERA",
-        "TITAN",
-        "CYCLOPS",
-        "MEDUSA",
-        "LIBRARY",
-        "MUSEUM",
-        "GALLERY",
-        "THEATER",
-        "CINEMA",
-        "CONCERT",
-        "STADIUM",
-        "ARENA",
-        "PLAZA",
-        "GARDEN",
-        "PARK",
-        "BRIDGE",
-        "TOWER",
-        "CASTLE",
-        "PALACE",
-        "TEMPLE",
-        "PYRAMID",
-        "GUITAR",
-        "PIANO",
-        "VIOLIN",
-        "TRUMPET",
-        "DRUMS",
-        "FLUTE",
-        "SAXOPHONE",
-        "HARP",
-        "ACCORDION",
-        "MELODY",
-        "HARMONY",
-        "RHYTHM",
-        "TEMPO",
-        "BEAR",
-        "CLOCK",
-        "WATCH",
-        "CALENDAR",
-        "DIAMOND",
-        "RUBY",
-        "EMERALD",
-        "SAPPHIRE",
-        "PEARL",
-        "JADE",
-        "OPAL",
-        "AMBER",
-        "CRYSTAL",
-        "GOLD",
-        "SILVER",
-        "PLATINUM",
-        "COPPER",
-        "IRON",
-        "STEEL",
-        "BRONZE",
-        "MARBLE",
-        "GRANITE",
-        "BREEZE",
-        "GUST",
-        "TORNADO",
-        "HURRICANE",
-        "TYPHOON",
-        "CYCLONE",
-        "BLIZZARD",
-        "AVALANCHE",
-        "TSUNAMI",
-        "EARTHQUAKE",
-        "PLANET",
-        "METEOR",
-        "ASTEROID",
-        "TELESCOPE",
-        "MICROSCOPE",
-        "COMPASS",
-        "SEXTANT",
-    ]
-    .iter()
-    .map(|byte| byte.to_string())
-    .collect()
+#[cfg(feature = "pqc_kyber")]
+/// Derives the key wrapping a secret from a Kyber shared secret.
+/// - Bound to the KEM ciphertext and `context`, so a shared secret is never used as a key directly.
+fn kyber_wrap_key(
+    shared_secret: &[u8],
+    ciphertext: &utils::kyber::KyberCipherText,
+    context: &[u8],
+) -> Result<Vec<u8>, Errors> {
+    let mut message = context.to_vec();
+    message.extend_from_slice(ciphertext.as_bytes());
+    calculate_hmac(shared_secret, &message)
+}
+
+/// Recovery phrase wordlist, one word per byte value.
+/// - Every word has a unique 3-letter prefix, so the first 3 letters are enough to identify it.
+/// - No two words are a single edit apart, so a typo never silently lands on another word.
+static RECOVERY_WORDS: [&str; 256] = [
+    "APPLE", "BANANA", "CHERRY", "DATE", "ALPHA", "OMEGA", "UPPER", "STAIR", "LOWER", "TABLE",
+    "CHAIR", "MEMORIES", "GAME", "VIDEO", "FOOTBALL", "CRICKET", "BALL", "COMPUTER", "BYTE",
+    "CODE", "LOGIC", "RUST", "PYTHON", "JAVA", "SWIFT", "KERNEL", "PIXEL", "BLOCK", "HASH", "MINT",
+    "SNOW", "RAVEN", "FALCON", "NOVA", "JUNO", "TANGO", "ECHO", "LIMA", "ZULU", "DELTA", "VICTOR",
+    "WHISKEY", "XRAY", "ZETA", "SIGMA", "THETA", "EPSILON", "NEON", "QUARK", "FLUX", "MOON",
+    "ORBIT", "SOLAR", "MARS", "VENUS", "PLUTO", "EARTH", "SATURN", "URANUS", "BINARY", "DECIMAL",
+    "CIPHER", "MATRIX", "JUPITER", "NEPTUNE", "MERCURY", "GALAXY", "NEBULA", "COSMIC", "PARTICLE",
+    "ATOM", "ELECTRON", "PROTON", "NEUTRON", "WAVE", "ENERGY", "LASER", "PLASMA", "ROCKET",
+    "SHUTTLE", "ROVER", "LANDER", "MISSION", "LAUNCH", "CLOUD", "SERVER", "NETWORK", "ROUTER",
+    "FIREWALL", "WIFI", "ETHERNET", "MODEM", "BROWSER", "WEBSITE", "INTERNET", "DOMAIN", "EMAIL",
+    "PASSWORD", "USERNAME", "ACCOUNT", "AVATAR", "DIGITAL", "VIRTUAL", "REALITY", "AUGMENT",
+    "CRYPTO", "TOKEN", "WALLET", "SMART", "CONTRACT", "LEDGER", "VERIFY", "VALIDATE", "ENCRYPT",
+    "SECURE", "PRIVATE", "PUBLIC", "OCEAN", "LAKE", "MOUNTAIN", "FOREST", "DESERT", "ARCTIC",
+    "TROPICS", "PRAIRIE", "CANYON", "REEF", "ISLAND", "BEACH", "COAST", "VOLCANO", "GLACIER",
+    "STORM", "THUNDER", "RAINBOW", "SUNRISE", "HORIZON", "ZENITH", "NADIR", "DRAGON", "PHOENIX",
+    "GRIFFIN", "UNICORN", "KRAKEN", "HYDRA", "SPHINX", "PEGASUS", "CENTAUR", "SIREN", "CHIMERA",
+    "TITAN", "CYCLOPS", "MEDUSA", "LIBRARY", "MUSEUM", "CINEMA", "ARENA", "GARDEN", "BRIDGE",
+    "CASTLE", "PALACE", "TEMPLE", "PYRAMID", "GUITAR", "PIANO", "VIOLIN", "TRUMPET", "DRUMS",
+    "HARP", "MELODY", "RHYTHM", "WATCH", "CALENDAR", "DIAMOND", "RUBY", "EMERALD", "SAPPHIRE",
+    "PEARL", "JADE", "OPAL", "AMBER", "GOLD", "SILVER", "COPPER", "IRON", "STEEL", "GRANITE",
+    "BREEZE", "TORNADO", "TYPHOON", "BLIZZARD", "TSUNAMI", "METEOR", "ASTEROID", "SEXTANT",
+    "ABSORB", "ACORN", "ACTOR", "ADMIRAL", "ADVICE", "AGENDA", "AIRPORT", "ALBUM", "ALMOND",
+    "ANCHOR", "ANGLE", "ANIMAL", "ANTENNA", "APRON", "ARMOR", "ARROW", "ARTIST", "ASPEN", "ATLAS",
+    "AUTUMN", "AVOCADO", "AWARD", "AXIS", "BADGE", "BAKER", "BAMBOO", "BARREL", "BASKET",
+    "BATTERY", "BEETLE", "BELT", "BERRY", "BICYCLE", "BISCUIT", "BLANKET", "BOAT", "BONFIRE",
+    "BORDER", "BOTTLE", "BOUQUET", "BRACELET", "BUBBLE", "BUCKET", "BUFFALO", "BUTTON", "BUZZER",
+    "CABIN", "CACTUS", "CAMEL", "CAPTAIN", "CARPET", "CELLAR", "CEMENT", "CIRCUS", "CITIZEN",
+    "COBRA", "COCONUT", "COFFEE", "CORAL", "COTTON", "COUGAR",
+];
+
+/// Number of checksum words appended to each recovery phrase.
+const RECOVERY_CHECKSUM_WORDS: usize = 2;
+
+fn recovery_checksum(indices: &[u8]) -> [u8; RECOVERY_CHECKSUM_WORDS] {
+    let mut hash = Sha3_256::new();
+    hash.update(b"CRYSTALYST-recovery-checksum");
+    hash.update(indices);
+    let digest = hash.finalize();
+    [digest[0], digest[1]]
+}
+
+/// Optimal string alignment distance, counts insertions, deletions, substitutions and swaps.
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(table[i - 2][j - 2] + 1);
+            }
+            table[i][j] = best;
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+/// Resolves a word by exact match or by an unambiguous prefix of at least 3 letters.
+fn lookup_recovery_word(word: &str) -> Option<u8> {
+    let word = word.to_ascii_uppercase();
+    if word.len() < 3 {
+        return None;
+    }
+    RECOVERY_WORDS
+        .iter()
+        .position(|candidate| candidate.starts_with(word.as_str()))
+        .map(|index| index as u8)
+}
+
+/// Returns the indices of the words within 2 edits of `word`, closest first.
+fn recovery_word_suggestions(word: &str) -> Vec<u8> {
+    let word = word.to_ascii_uppercase();
+    let mut candidates: Vec<(usize, u8)> = RECOVERY_WORDS
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| {
+            (
+                edit_distance(word.as_bytes(), candidate.as_bytes()),
+                i as u8,
+            )
+        })
+        .filter(|(distance, _)| *distance <= 2)
+        .collect();
+    candidates.sort_unstable();
+    candidates.truncate(3);
+    candidates.into_iter().map(|(_, i)| i).collect()
 }
 
-fn generate_recovery_key(key: &[u8], nonce: &[u8]) -> String {
-    let word_list = generate_full_wordlist();
+/// Decodes a phrase into word indices and verifies its checksum, without the checksum words.
+/// - A single unknown word is corrected when exactly one suggestion satisfies the checksum.
+fn decode_recovery_phrase(input: &str) -> Result<Vec<u8>, Errors> {
+    let words: Vec<&str> = input
+        .split(|c: char| c == '-' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words.len() < 32 + RECOVERY_CHECKSUM_WORDS {
+        return Err(Errors::InvalidRecoveryKey(format!(
+            "Expected at least {} words, got {}",
+            32 + RECOVERY_CHECKSUM_WORDS,
+            words.len()
+        )));
+    }
+
+    let mut indices = Vec::with_capacity(words.len());
+    let mut unknown = Vec::new();
+    for (position, word) in words.iter().enumerate() {
+        match lookup_recovery_word(word) {
+            Some(index) => indices.push(index),
+            None => {
+                unknown.push(position);
+                indices.push(0);
+            }
+        }
+    }
+
+    let checksum_ok = |indices: &[u8]| {
+        let (data, checksum) = indices.split_at(indices.len() - RECOVERY_CHECKSUM_WORDS);
+        recovery_checksum(data) == checksum
+    };
+
+    if let [position] = unknown[..] {
+        let suggestions = recovery_word_suggestions(words[position]);
+        let mut matching = suggestions.iter().filter(|&&index| {
+            indices[position] = index;
+            checksum_ok(&indices)
+        });
+        if let (Some(&index), None) = (matching.next(), matching.next()) {
+            indices[position] = index;
+            unknown.clear();
+        }
+    }
+
+    if let Some(&position) = unknown.first() {
+        indices.zeroize();
+        let suggestions: Vec<&str> = recovery_word_suggestions(words[position])
+            .into_iter()
+            .map(|index| RECOVERY_WORDS[index as usize])
+            .collect();
+        let hint = if suggestions.is_empty() {
+            String::new()
+        } else {
+            format!(", did you mean {}?", suggestions.join(" or "))
+        };
+        return Err(Errors::InvalidRecoveryKey(format!(
+            "Unknown word at position {}{}",
+            position + 1,
+            hint
+        )));
+    }
+
+    if !checksum_ok(&indices) {
+        indices.zeroize();
+        return Err(Errors::InvalidRecoveryKey(
+            "Checksum mismatch, a word is mistyped, missing or out of order".to_string(),
+        ));
+    }
+
+    indices.truncate(indices.len() - RECOVERY_CHECKSUM_WORDS);
+    Ok(indices)
+}
+
+fn generate_recovery_key(key: &[u8], nonce: &[u8]) -> RecoveryKey {
     let mut key = key.to_vec();
 
     key.iter_mut()
         .enumerate()
         .for_each(|(i, b)| *b = b.wrapping_add(nonce[i % nonce.len()]));
 
+    let checksum = recovery_checksum(&key);
     let words: Vec<&str> = key
         .iter()
-        .map(|&byte| word_list[byte as usize].as_str())
+        .chain(checksum.iter())
+        .map(|&byte| RECOVERY_WORDS[byte as usize])
         .collect();
+    key.zeroize();
 
-    words.join("-")
+    RecoveryKey::new(words.join("-"))
 }
 
 fn parse_recovery_key(input: &str, nonce: &[u8]) -> Result<Vec<u8>, Errors> {
-    let word_list = generate_full_wordlist();
-
-    let word_map: std::collections::HashMap<String, u8> = word_list
-        .iter()
-        .enumerate()
-        .map(|(i, word)| (word.to_ascii_uppercase(), i as u8))
-        .collect();
-
-    let mut key = input
-        .split('-')
-        .map(|word| word_map.get(&word.to_ascii_uppercase()).copied())
-        .collect::<Option<Vec<u8>>>()
-        .ok_or_else(|| Errors::EmptyPassword)?;
+    let mut key = decode_recovery_phrase(input)?;
 
     key.iter_mut()
         .enumerate()
@@ -105,7 +105,7 @@ pub enum NonceType {
     Classic,
     Hashed,
     Tagged(String),
-    #[cfg(feature = "base_coding")]
+    #[cfg(feature = "machine_rng")]
     Machine,
     Tpm(Hardware, TpmModule, tss_esapi::Context),
 }
@@ -125,7 +125,7 @@ impl Nonce {
                 let rng = rng.ok_or(Errors::RngRequired)?;
                 Ok(Nonce::tagged_nonce(rng, &tag.as_bytes()))
             }
-            #[cfg(feature = "base_coding")]
+            #[cfg(feature = "machine_rng")]
             NonceType::Machine => Ok(Nonce::machine_nonce(rng)),
             NonceType::Tpm(hardware, mut manager, mut tpm) => {
                 Nonce::tpm_nonce(hardware, &mut manager, &mut tpm)
@@ -174,7 +174,7 @@ impl Nonce {
         NonceData::TaggedNonce(output) // Hash the nonce to get a 32 byte more random nonce (Extra Security)
     }
 
-    #[cfg(feature = "base_coding")]
+    #[cfg(feature = "machine_rng")]
     fn machine_nonce(rng: Option<RNG>) -> NonceData {
         let user_name = whoami::username();
         let device_name = whoami::devicename();
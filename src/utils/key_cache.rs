@@ -0,0 +1,174 @@
+//! # Key Cache
+//!
+//! Caches the seeds derived from key and nonce for the dynamic S-box, so repeated operations with
+//! the same key (or TPM hashing) skip the derivation.
+//! - Entries are looked up by a SHA3-256 hash of key and nonce, the key material itself is never stored.
+//! - Least recently used entries are evicted once the capacity is reached.
+//! - Cached seeds are zeroized when evicted, purged or when the cache is dropped.
+//! - Stream chunks use a fresh key each, so they bypass the cache.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Mutex, OnceLock},
+};
+
+use secrecy::{ExposeSecret, SecretBox};
+use sha3::{Digest, Sha3_256};
+
+use crate::Errors;
+
+/// Capacity of the global cache used by the block cipher.
+pub const DEFAULT_CAPACITY: usize = 16;
+
+static GLOBAL_KEY_CACHE: OnceLock<KeyCache> = OnceLock::new();
+
+struct Entry {
+    seed: SecretBox<[u8]>,
+    last_used: u64,
+}
+
+struct CacheState {
+    capacity: usize,
+    clock: u64,
+    entries: HashMap<[u8; 32], Entry>,
+    /// Entry ids ordered by last use, oldest first.
+    order: BTreeMap<u64, [u8; 32]>,
+}
+
+impl CacheState {
+    fn evict_to(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            match self.order.pop_first() {
+                Some((_, id)) => {
+                    self.entries.remove(&id);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// LRU cache of derived key seeds.
+/// - A capacity of 0 disables the cache, every lookup derives the seed again.
+/// - Safe to share between threads.
+pub struct KeyCache {
+    state: Mutex<CacheState>,
+}
+
+impl KeyCache {
+    /// Creates a cache holding at most `capacity` seeds.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                capacity,
+                clock: 0,
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Returns the process-wide cache used by `CrystalystBuilder`, created with `DEFAULT_CAPACITY`.
+    /// - Call `purge` once the keys are no longer needed, or `set_capacity(0)` to disable caching.
+    pub fn global() -> &'static KeyCache {
+        GLOBAL_KEY_CACHE.get_or_init(|| KeyCache::new(DEFAULT_CAPACITY))
+    }
+
+    /// Returns the maximum number of cached seeds.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Changes the capacity, evicting the least recently used seeds if needed.
+    /// - 0 disables the cache and drops every seed.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut state = self.lock();
+        state.capacity = capacity;
+        state.evict_to(capacity);
+    }
+
+    /// Returns `true` if the capacity is above 0.
+    pub fn is_enabled(&self) -> bool {
+        self.capacity() > 0
+    }
+
+    /// Returns the number of cached seeds.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if no seed is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops and zeroizes every cached seed, the capacity is kept.
+    pub fn purge(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.order.clear();
+    }
+
+    /// Returns the cached seed for `key` and `nonce`, or derives and caches it.
+    /// - `derive` runs without holding the lock, concurrent misses may derive the same seed twice.
+    pub fn get_or_derive<F>(&self, key: &[u8], nonce: &[u8], derive: F) -> Result<Vec<u8>, Errors>
+    where
+        F: FnOnce() -> Result<Vec<u8>, Errors>,
+    {
+        let id = entry_id(key, nonce);
+
+        {
+            let mut state = self.lock();
+            if state.capacity == 0 {
+                drop(state);
+                return derive();
+            }
+
+            state.clock += 1;
+            let now = state.clock;
+            if let Some(entry) = state.entries.get_mut(&id) {
+                let previous = entry.last_used;
+                entry.last_used = now;
+                let seed = entry.seed.expose_secret().to_vec();
+                state.order.remove(&previous);
+                state.order.insert(now, id);
+                return Ok(seed);
+            }
+        }
+
+        let seed = derive()?;
+
+        let mut state = self.lock();
+        if state.capacity > 0 {
+            state.clock += 1;
+            let now = state.clock;
+            let entry = Entry {
+                seed: SecretBox::new(seed.clone().into_boxed_slice()),
+                last_used: now,
+            };
+            if let Some(previous) = state.entries.insert(id, entry) {
+                state.order.remove(&previous.last_used);
+            }
+            state.order.insert(now, id);
+
+            let capacity = state.capacity;
+            state.evict_to(capacity);
+        }
+
+        Ok(seed)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Lookup id of a key and nonce pair.
+fn entry_id(key: &[u8], nonce: &[u8]) -> [u8; 32] {
+    let mut hash = Sha3_256::new();
+    hash.update(b"CRYSTALYST-key-cache");
+    hash.update((key.len() as u64).to_le_bytes());
+    hash.update(key);
+    hash.update(nonce);
+    hash.finalize().into()
+}
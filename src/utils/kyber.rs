@@ -6,8 +6,12 @@
 //! ## Features
 //! - Post-quantum secure key encapsulation
 //! - Unilaterally authenticated key exchange
+//! - Mutually authenticated key exchange with per-session state
+//! - Fingerprints and trust store checks before a handshake (see `utils::trust`)
 //! - Memory-safe secret handling using `secrecy` crate
 //! - Support for both fresh key generation and importing existing keys
+//! - Binary and armored key files, private keys are always stored encrypted under a password
+//! - Kyber-512 (default), Kyber-768 or Kyber-1024, selected with the `kyber512`/`kyber768`/`kyber1024` features
 //!
 //! ## Example Usage
 //!
@@ -44,49 +48,88 @@
 //! let client_secret = client.shared_secret();
 //! let server_secret = server.shared_secret();
 //! ```
-#[cfg(feature = "kyber")]
-use crate::Errors;
-#[cfg(feature = "kyber")]
-use pqc_kyber::Uake;
-#[cfg(feature = "kyber")]
+//!
+//! ### AKE Protocol
+//! ```rust
+//! # use crystalyst_rs::utils::kyber::KyberModule;
+//! # fn main() -> Result<(), crystalyst_rs::Errors> {
+//! let client = KyberModule::new()?;
+//! let server = KyberModule::new()?;
+//!
+//! // Both sides know the other's static public key
+//! let (mut client_session, send_a) = client.ake_client_init(&server.public)?;
+//! let (server_session, server_send) = server.ake_server_receive(&client.public, &send_a)?;
+//! client.ake_client_confirm(&mut client_session, &server_send)?;
+//!
+//! let client_secret = client_session.shared_secret()?;
+//! let server_secret = server_session.shared_secret()?;
+//! # Ok(())
+//! # }
+//! ```
+#[cfg(feature = "pqc_kyber")]
+use crate::{
+    Errors,
+    utils::trust::{Fingerprint, TrustStore},
+};
+#[cfg(feature = "pqc_kyber")]
+use pqc_kyber::{Ake, Uake};
+#[cfg(feature = "pqc_kyber")]
 use rand::rngs::OsRng;
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 use secrecy::{ExposeSecret, ExposeSecretMut, SecretBox};
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+use sha3::{Digest, Sha3_256};
+
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+use crate::utils::base_utils::AsBase;
+#[cfg(all(feature = "pqc_kyber", feature = "key_derivation"))]
+use crate::{
+    Config,
+    cipher::block_cipher::{CrystalystBuilder, Utils},
+    rng_utils::{
+        nonce::{Nonce, NonceType},
+        rng::RNG,
+        salt::Salt,
+    },
+};
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+use base64::{Engine, prelude::BASE64_STANDARD};
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 /// A securely wrapped shared secret derived from Kyber operations.
 ///
 /// The secret is stored in a `SecretBox` to prevent accidental exposure
 /// and ensure it's properly zeroized when dropped.
 pub struct SharedSecret(SecretBox<[u8]>);
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 /// Kyber ciphertext used for key encapsulation transport.
 ///
 /// This contains the encapsulated key material that can be sent over
 /// an insecure channel to the recipient.
 pub struct KyberCipherText(Vec<u8>);
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 /// Kyber public key for key encapsulation.
 ///
 /// This is safe to share publicly and is used by others to encapsulate
 /// secrets that only the corresponding private key holder can decrypt.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KyberPublicKey(Vec<u8>);
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 /// Kyber private key stored securely.
 ///
 /// The private key is wrapped in a `SecretBox` to prevent accidental
 /// exposure and ensure secure memory handling.
 pub struct KyberPrivateKey(SecretBox<[u8]>);
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 impl KyberPublicKey {
     /// Creates a new public key from raw bytes.
     ///
     /// # Arguments
-    /// * `public` - Raw public key bytes (should be 800 bytes for Kyber)
+    /// * `public` - Raw public key bytes (`KyberModule::PUBLIC_KEY_SIZE` bytes)
     pub fn new(public: Vec<u8>) -> Self {
         Self(public)
     }
@@ -97,16 +140,30 @@ impl KyberPublicKey {
     pub fn as_bytes(&self) -> &[u8] {
         &self.0
     }
+
+    /// Encapsulates a fresh shared secret to the holder of this public key.
+    ///
+    /// # Returns
+    /// * `Ok((SharedSecret, KyberCipherText))` - The secret and the ciphertext to send to the key holder
+    /// * `Err(Errors)` - Invalid public key size or encapsulation failed
+    pub fn encapsulate(&self) -> Result<(SharedSecret, KyberCipherText), Errors> {
+        let (ciphertext, shared_secret) = pqc_kyber::encapsulate(&self.0, &mut OsRng)
+            .map_err(|e| Errors::KyberError(e.to_string()))?;
+        Ok((
+            SharedSecret::new(shared_secret.to_vec()),
+            KyberCipherText::new(ciphertext.to_vec()),
+        ))
+    }
 }
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 impl KyberPrivateKey {
     /// Creates a new private key from raw bytes.
     ///
     /// The key material is immediately wrapped in a `SecretBox` for security.
     ///
     /// # Arguments
-    /// * `secret` - Raw private key bytes (should be 1632 bytes for Kyber)
+    /// * `secret` - Raw private key bytes (`KyberModule::SECRET_KEY_SIZE` bytes)
     pub fn new(secret: Vec<u8>) -> Self {
         Self(SecretBox::new(secret.into_boxed_slice()))
     }
@@ -118,9 +175,20 @@ impl KyberPrivateKey {
     pub fn expose_secret(&self) -> &[u8] {
         &self.0.expose_secret()
     }
+
+    /// Decapsulates the shared secret from a ciphertext addressed to this private key.
+    ///
+    /// # Returns
+    /// * `Ok(SharedSecret)` - The decapsulated shared secret (32 bytes)
+    /// * `Err(Errors)` - Invalid ciphertext or key size
+    pub fn decapsulate(&self, cipher: &KyberCipherText) -> Result<SharedSecret, Errors> {
+        let shared_secret = pqc_kyber::decapsulate(cipher.as_bytes(), self.expose_secret())
+            .map_err(|e| Errors::KyberError(e.to_string()))?;
+        Ok(SharedSecret::new(shared_secret.to_vec()))
+    }
 }
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 impl SharedSecret {
     /// Creates a new shared secret from raw bytes.
     ///
@@ -141,7 +209,7 @@ impl SharedSecret {
     }
 }
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 impl KyberCipherText {
     /// Creates a new ciphertext from raw bytes.
     ///
@@ -159,7 +227,374 @@ impl KyberCipherText {
     }
 }
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
+impl Clone for KyberPrivateKey {
+    fn clone(&self) -> Self {
+        Self::new(self.expose_secret().to_vec())
+    }
+}
+
+#[cfg(feature = "pqc_kyber")]
+impl std::fmt::Debug for KyberPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("KyberPrivateKey([REDACTED])")
+    }
+}
+
+#[cfg(feature = "pqc_kyber")]
+/// Kyber parameter set.
+///
+/// The level is chosen per build with the `kyber512`, `kyber768` or `kyber1024` crate features,
+/// since the underlying implementation is compiled for a single parameter set, enabling more than one is a compile error.
+/// `kyber` alone uses Kyber-512.
+///
+/// * Kyber-512 aims at security roughly equivalent to AES-128
+/// * Kyber-768 aims at security roughly equivalent to AES-192
+/// * Kyber-1024 aims at security roughly equivalent to AES-256
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KyberLevel {
+    Kyber512,
+    Kyber768,
+    Kyber1024,
+}
+
+#[cfg(feature = "pqc_kyber")]
+impl KyberLevel {
+    /// Level this build was compiled for.
+    pub const CURRENT: KyberLevel = match pqc_kyber::KYBER_K {
+        2 => KyberLevel::Kyber512,
+        4 => KyberLevel::Kyber1024,
+        _ => KyberLevel::Kyber768,
+    };
+
+    /// Returns the Kyber module rank `k` of the level.
+    pub fn k(self) -> u8 {
+        match self {
+            KyberLevel::Kyber512 => 2,
+            KyberLevel::Kyber768 => 3,
+            KyberLevel::Kyber1024 => 4,
+        }
+    }
+
+    fn from_k(k: u8) -> Option<Self> {
+        match k {
+            2 => Some(KyberLevel::Kyber512),
+            3 => Some(KyberLevel::Kyber768),
+            4 => Some(KyberLevel::Kyber1024),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "pqc_kyber")]
+impl std::fmt::Display for KyberLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KyberLevel::Kyber512 => f.write_str("Kyber512"),
+            KyberLevel::Kyber768 => f.write_str("Kyber768"),
+            KyberLevel::Kyber1024 => f.write_str("Kyber1024"),
+        }
+    }
+}
+
+// -----------------------------------------------------
+// Key encoding
+//
+// Binary layout: magic `CRKY` (4), version (1), key type (1), Kyber K (1), body.
+// Public keys carry the raw key as body, private keys a CRYSTALYST ciphertext of the raw key
+// with the first 7 bytes as associated data.
+//
+// Armored keys wrap the binary form in PEM-style lines with a 3-byte SHA3-256 checksum:
+//
+//     -----BEGIN CRYSTALYST KYBER PUBLIC KEY-----
+//     Level: Kyber768
+//
+//     <base64, 64 characters per line>
+//     =<base64 checksum>
+//     -----END CRYSTALYST KYBER PUBLIC KEY-----
+
+#[cfg(feature = "pqc_kyber")]
+const KEY_MAGIC: [u8; 4] = *b"CRKY";
+#[cfg(feature = "pqc_kyber")]
+const KEY_VERSION: u8 = 1;
+#[cfg(feature = "pqc_kyber")]
+const KEY_PREFIX_LEN: usize = 7;
+#[cfg(feature = "pqc_kyber")]
+const PUBLIC_KEY_TYPE: u8 = 1;
+#[cfg(all(feature = "pqc_kyber", feature = "key_derivation"))]
+const ENCRYPTED_PRIVATE_KEY_TYPE: u8 = 2;
+/// Shortest password accepted for private keys, the block cipher checks it before Argon2 runs.
+#[cfg(all(feature = "pqc_kyber", feature = "key_derivation"))]
+pub const MIN_KEY_PASSWORD_LEN: usize = 32;
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+const PUBLIC_KEY_LABEL: &str = "CRYSTALYST KYBER PUBLIC KEY";
+#[cfg(all(
+    feature = "pqc_kyber",
+    feature = "base_coding",
+    feature = "key_derivation"
+))]
+const PRIVATE_KEY_LABEL: &str = "CRYSTALYST KYBER ENCRYPTED PRIVATE KEY";
+
+#[cfg(feature = "pqc_kyber")]
+fn key_prefix(key_type: u8) -> [u8; KEY_PREFIX_LEN] {
+    let [m0, m1, m2, m3] = KEY_MAGIC;
+    [
+        m0,
+        m1,
+        m2,
+        m3,
+        KEY_VERSION,
+        key_type,
+        KyberLevel::CURRENT.k(),
+    ]
+}
+
+#[cfg(feature = "pqc_kyber")]
+fn level_name(k: u8) -> String {
+    match KyberLevel::from_k(k) {
+        Some(level) => level.to_string(),
+        None => format!("Kyber K={}", k),
+    }
+}
+
+/// Validates the binary prefix and returns the body.
+#[cfg(feature = "pqc_kyber")]
+fn key_body(data: &[u8], key_type: u8) -> Result<&[u8], Errors> {
+    if data.len() < KEY_PREFIX_LEN || data[..4] != KEY_MAGIC {
+        return Err(Errors::KyberError(
+            "Missing CRYSTALYST key header".to_string(),
+        ));
+    } else if data[4] != KEY_VERSION {
+        return Err(Errors::KyberError(format!(
+            "Unsupported key version: {}",
+            data[4]
+        )));
+    } else if data[5] != key_type {
+        return Err(Errors::KyberError(format!(
+            "Unexpected key type {}, expected {}",
+            data[5], key_type
+        )));
+    } else if data[6] != KyberLevel::CURRENT.k() {
+        return Err(Errors::KyberError(format!(
+            "Key is {}, this build uses {}",
+            level_name(data[6]),
+            KyberLevel::CURRENT
+        )));
+    }
+
+    Ok(&data[KEY_PREFIX_LEN..])
+}
+
+/// Rejects private key passwords the block cipher would refuse, naming the requirement.
+#[cfg(all(feature = "pqc_kyber", feature = "key_derivation"))]
+fn check_key_password(password: &[u8]) -> Result<(), Errors> {
+    if password.len() < MIN_KEY_PASSWORD_LEN {
+        return Err(Errors::KyberError(format!(
+            "Private key password must be at least {} bytes, got {}",
+            MIN_KEY_PASSWORD_LEN,
+            password.len()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+fn armor_checksum(data: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(data)[..3].to_vec()
+}
+
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+fn armor(label: &str, data: &[u8]) -> String {
+    let body = data.to_vec().as_base64();
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    out.push_str(&format!("Level: {}\n\n", KyberLevel::CURRENT));
+    for line in body.as_bytes().chunks(64) {
+        out.push_str(&String::from_utf8_lossy(line));
+        out.push('\n');
+    }
+    out.push_str(&format!("={}\n", armor_checksum(data).as_base64()));
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Strips the armor lines and verifies the checksum, header lines are informational only.
+#[cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+fn dearmor(label: &str, text: &str) -> Result<Vec<u8>, Errors> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let mut lines = text.lines().map(str::trim);
+
+    if !lines.by_ref().any(|line| line == begin) {
+        return Err(Errors::KyberError(format!("Missing `{}` line", begin)));
+    }
+
+    let mut body = String::new();
+    let mut checksum = None;
+    let mut in_headers = true;
+    let mut closed = false;
+    for line in lines {
+        if line == end {
+            closed = true;
+            break;
+        } else if in_headers && line.contains(':') {
+            continue;
+        } else if line.is_empty() {
+            in_headers = false;
+        } else if let Some(value) = line.strip_prefix('=') {
+            checksum = Some(value.to_string());
+        } else {
+            in_headers = false;
+            body.push_str(line);
+        }
+    }
+
+    if !closed {
+        return Err(Errors::KyberError(format!("Missing `{}` line", end)));
+    }
+
+    let data = BASE64_STANDARD
+        .decode(body)
+        .map_err(|e| Errors::Base64DecodeFailed(e.to_string()))?;
+    let checksum =
+        checksum.ok_or_else(|| Errors::KyberError("Missing armor checksum".to_string()))?;
+    let checksum = BASE64_STANDARD
+        .decode(checksum)
+        .map_err(|e| Errors::Base64DecodeFailed(e.to_string()))?;
+    if checksum != armor_checksum(&data) {
+        return Err(Errors::KyberError(
+            "Armor checksum mismatch, the key is damaged".to_string(),
+        ));
+    }
+
+    Ok(data)
+}
+
+#[cfg(feature = "pqc_kyber")]
+impl KyberPublicKey {
+    /// Serializes the key into the binary key format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&key_prefix(PUBLIC_KEY_TYPE)[..], &self.0].concat()
+    }
+
+    /// Returns the SHA3-256 fingerprint of the key, see `Fingerprint::to_words` for a readable form.
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of(self)
+    }
+
+    /// Parses a key produced by `to_bytes`.
+    ///
+    /// # Errors
+    /// Returns `Errors::KyberError` for a malformed key or a key of another Kyber level.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Errors> {
+        let body = key_body(data, PUBLIC_KEY_TYPE)?;
+        if body.len() != pqc_kyber::KYBER_PUBLICKEYBYTES {
+            return Err(Errors::KyberError(format!(
+                "Public key must be {} bytes long for {}, got {}",
+                pqc_kyber::KYBER_PUBLICKEYBYTES,
+                KyberLevel::CURRENT,
+                body.len()
+            )));
+        }
+
+        Ok(Self(body.to_vec()))
+    }
+
+    /// Serializes the key into armored text, safe for config files and mail.
+    #[cfg(feature = "base_coding")]
+    pub fn to_armored(&self) -> String {
+        armor(PUBLIC_KEY_LABEL, &self.to_bytes())
+    }
+
+    /// Parses a key produced by `to_armored`.
+    /// - Surrounding text and whitespace around the armor lines are ignored.
+    #[cfg(feature = "base_coding")]
+    pub fn from_armored(text: &str) -> Result<Self, Errors> {
+        Self::from_bytes(&dearmor(PUBLIC_KEY_LABEL, text)?)
+    }
+}
+
+#[cfg(all(feature = "pqc_kyber", feature = "key_derivation"))]
+impl KyberPrivateKey {
+    /// Encrypts the key under `password` into the binary key format.
+    /// - The key is encrypted with the block cipher, Argon2 is always enabled with a fresh salt.
+    /// - `config` selects rounds and Argon2 parameters.
+    /// - The password must be at least `MIN_KEY_PASSWORD_LEN` bytes even though Argon2 runs,
+    ///   hash shorter passphrases into 32 bytes first.
+    ///
+    /// # Errors
+    /// Returns `Errors::KyberError` if the password is too short.
+    pub fn to_encrypted_bytes(&self, password: &[u8], config: Config) -> Result<Vec<u8>, Errors> {
+        check_key_password(password)?;
+        let prefix = key_prefix(ENCRYPTED_PRIVATE_KEY_TYPE);
+        let mut out = Vec::new();
+        CrystalystBuilder::new()
+            .data(self.expose_secret())
+            .password(password)
+            .nonce(Nonce::generate_nonce(
+                Some(RNG::osrng()),
+                NonceType::Classic,
+            )?)
+            .salt(Salt::salt())
+            .config(config.key_derivation(true))
+            .associated_data(&prefix)
+            .utils(Utils::new().wrap_all(true))
+            .encrypt(&mut out)?;
+
+        Ok([&prefix[..], &out].concat())
+    }
+
+    /// Decrypts a key produced by `to_encrypted_bytes`.
+    /// - `config` only supplies runtime options, Argon2 parameters are read from the key.
+    ///
+    /// # Errors
+    /// Returns `Errors::KyberError` for a malformed key or a password shorter than `MIN_KEY_PASSWORD_LEN`,
+    /// and the block cipher errors for a wrong password.
+    pub fn from_encrypted_bytes(
+        data: &[u8],
+        password: &[u8],
+        config: Config,
+    ) -> Result<Self, Errors> {
+        check_key_password(password)?;
+        let body = key_body(data, ENCRYPTED_PRIVATE_KEY_TYPE)?;
+        let mut secret = Vec::with_capacity(pqc_kyber::KYBER_SECRETKEYBYTES);
+        CrystalystBuilder::new()
+            .data(body)
+            .password(password)
+            .config(config)
+            .associated_data(&data[..KEY_PREFIX_LEN])
+            .decrypt(&mut secret)?;
+        let key = Self::new(secret);
+
+        if key.expose_secret().len() != pqc_kyber::KYBER_SECRETKEYBYTES {
+            return Err(Errors::KyberError(format!(
+                "Private key must be {} bytes long for {}, got {}",
+                pqc_kyber::KYBER_SECRETKEYBYTES,
+                KyberLevel::CURRENT,
+                key.expose_secret().len()
+            )));
+        }
+
+        Ok(key)
+    }
+
+    /// Encrypts the key under `password` into armored text, see `to_encrypted_bytes`.
+    #[cfg(feature = "base_coding")]
+    pub fn to_armored(&self, password: &[u8], config: Config) -> Result<String, Errors> {
+        Ok(armor(
+            PRIVATE_KEY_LABEL,
+            &self.to_encrypted_bytes(password, config)?,
+        ))
+    }
+
+    /// Decrypts a key produced by `to_armored`.
+    #[cfg(feature = "base_coding")]
+    pub fn from_armored(text: &str, password: &[u8], config: Config) -> Result<Self, Errors> {
+        Self::from_encrypted_bytes(&dearmor(PRIVATE_KEY_LABEL, text)?, password, config)
+    }
+}
+
+#[cfg(feature = "pqc_kyber")]
 /// Main Kyber cryptographic module supporting both KEM and UAKE operations.
 ///
 /// This struct maintains a Kyber keypair and UAKE state for performing
@@ -167,8 +602,8 @@ impl KyberCipherText {
 /// in `SecretBox` wrappers for memory safety.
 ///
 /// # Thread Safety
-/// This struct is not thread-safe due to mutable UAKE state. Create separate
-/// instances for concurrent operations.
+/// The UAKE methods mutate the embedded state, create separate instances for concurrent
+/// UAKE handshakes. The AKE methods only borrow the module and keep their state in `AkeSession`.
 pub struct KyberModule {
     /// The public key component of the Kyber keypair
     pub public: KyberPublicKey,
@@ -178,18 +613,24 @@ pub struct KyberModule {
     pub uake: SecretBox<Uake>,
 }
 
-#[cfg(feature = "kyber")]
+#[cfg(feature = "pqc_kyber")]
 impl KyberModule {
-    /// Standard Kyber public key size in bytes
-    pub const PUBLIC_KEY_SIZE: usize = 800;
+    /// Kyber level this build was compiled for
+    pub const LEVEL: KyberLevel = KyberLevel::CURRENT;
+    /// Kyber public key size in bytes (800, 1184 or 1568 for Kyber-512/768/1024)
+    pub const PUBLIC_KEY_SIZE: usize = pqc_kyber::KYBER_PUBLICKEYBYTES;
     /// Size of UAKE client initial message in bytes
-    pub const UAKE_SEND_A_SIZE: usize = 1568;
-    /// Standard Kyber private key size in bytes
-    pub const SECRET_KEY_SIZE: usize = 1632;
+    pub const UAKE_SEND_A_SIZE: usize = pqc_kyber::UAKE_INIT_BYTES;
+    /// Kyber private key size in bytes (1632, 2400 or 3168 for Kyber-512/768/1024)
+    pub const SECRET_KEY_SIZE: usize = pqc_kyber::KYBER_SECRETKEYBYTES;
     /// Size of derived shared secret in bytes
-    pub const SHARED_SECRET_SIZE: usize = 32;
+    pub const SHARED_SECRET_SIZE: usize = pqc_kyber::KYBER_SSBYTES;
     /// Size of UAKE server response in bytes
-    pub const SERVER_SEND_KEY_SIZE: usize = 768;
+    pub const SERVER_SEND_KEY_SIZE: usize = pqc_kyber::UAKE_RESPONSE_BYTES;
+    /// Size of AKE client initial message in bytes
+    pub const AKE_SEND_A_SIZE: usize = pqc_kyber::AKE_INIT_BYTES;
+    /// Size of AKE server response in bytes
+    pub const AKE_SERVER_SEND_SIZE: usize = pqc_kyber::AKE_RESPONSE_BYTES;
 
     /// Creates a new Kyber module with a freshly generated keypair.
     ///
@@ -220,8 +661,8 @@ impl KyberModule {
     /// keys generated elsewhere. The UAKE state is always initialized fresh.
     ///
     /// # Arguments
-    /// * `public` - Public key bytes (must be exactly 800 bytes)
-    /// * `secret` - Private key bytes (must be exactly 1632 bytes)
+    /// * `public` - Public key bytes (must be exactly `PUBLIC_KEY_SIZE` bytes)
+    /// * `secret` - Private key bytes (must be exactly `SECRET_KEY_SIZE` bytes)
     /// * `uake` - Optional existing UAKE state, creates new if None
     ///
     /// # Returns
@@ -230,8 +671,8 @@ impl KyberModule {
     ///
     /// # Example
     /// ```rust
-    /// let public_bytes = [0u8; 800]; // Your actual public key
-    /// let secret_bytes = [0u8; 1632]; // Your actual private key
+    /// let public_bytes = [0u8; KyberModule::PUBLIC_KEY_SIZE]; // Your actual public key
+    /// let secret_bytes = [0u8; KyberModule::SECRET_KEY_SIZE]; // Your actual private key
     /// let kyber = KyberModule::new_from_existed(&public_bytes, &secret_bytes, None)?;
     /// ```
     pub fn new_from_existed(
@@ -241,7 +682,8 @@ impl KyberModule {
     ) -> Result<Self, Errors> {
         if public.len() != Self::PUBLIC_KEY_SIZE || secret.len() != Self::SECRET_KEY_SIZE {
             return Err(Errors::KyberError(format!(
-                "Invalid key sizes: public must be {} bytes (got {}), secret must be {} bytes (got {})",
+                "Invalid key sizes for {}: public must be {} bytes (got {}), secret must be {} bytes (got {})",
+                Self::LEVEL,
                 Self::PUBLIC_KEY_SIZE,
                 public.len(),
                 Self::SECRET_KEY_SIZE,
@@ -267,7 +709,7 @@ impl KyberModule {
     /// * `public` - Server's public key for the exchange
     ///
     /// # Returns
-    /// * `Ok(Vec<u8>)` - Client's initial message (1568 bytes) to send to server
+    /// * `Ok(Vec<u8>)` - Client's initial message (`UAKE_SEND_A_SIZE` bytes) to send to server
     /// * `Err(Errors)` - Invalid public key size or protocol error
     ///
     /// # Example
@@ -280,8 +722,9 @@ impl KyberModule {
     pub fn client_init(&mut self, public: KyberPublicKey) -> Result<Vec<u8>, Errors> {
         if public.as_bytes().len() != Self::PUBLIC_KEY_SIZE {
             return Err(Errors::KyberError(format!(
-                "Public key must be {} bytes long, got {}",
+                "Public key must be {} bytes long for {}, got {}",
                 Self::PUBLIC_KEY_SIZE,
+                KyberLevel::CURRENT,
                 public.as_bytes().len()
             )));
         }
@@ -297,16 +740,31 @@ impl KyberModule {
             .to_vec())
     }
 
+    /// Like `client_init`, after checking `public` against `trust` for peer `server`.
+    /// - An unknown server is pinned if the store has TOFU enabled.
+    ///
+    /// # Errors
+    /// Returns `Errors::UntrustedKey` if the store rejects the key, no handshake message is produced then.
+    pub fn client_init_verified(
+        &mut self,
+        server: &str,
+        public: KyberPublicKey,
+        trust: &mut TrustStore,
+    ) -> Result<Vec<u8>, Errors> {
+        trust.check(server, &public)?;
+        self.client_init(public)
+    }
+
     /// Processes client's UAKE message as a server.
     ///
     /// This is the second step in the UAKE protocol where the server
     /// receives the client's initial message and generates a response.
     ///
     /// # Arguments
-    /// * `send_a` - Client's initial message (must be exactly 1568 bytes)
+    /// * `send_a` - Client's initial message (must be exactly `UAKE_SEND_A_SIZE` bytes)
     ///
     /// # Returns
-    /// * `Ok(Vec<u8>)` - Server's response message (768 bytes) to send back to client
+    /// * `Ok(Vec<u8>)` - Server's response message (`SERVER_SEND_KEY_SIZE` bytes) to send back to client
     /// * `Err(Errors)` - Invalid message size or protocol error
     ///
     /// # Example
@@ -319,8 +777,9 @@ impl KyberModule {
     pub fn server_receive(&mut self, send_a: Vec<u8>) -> Result<Vec<u8>, Errors> {
         if send_a.len() != Self::UAKE_SEND_A_SIZE {
             return Err(Errors::KyberError(format!(
-                "Client message must be {} bytes long, got {}",
+                "Client message must be {} bytes long for {}, got {}",
                 Self::UAKE_SEND_A_SIZE,
+                KyberLevel::CURRENT,
                 send_a.len()
             )));
         }
@@ -345,7 +804,7 @@ impl KyberModule {
     /// After this call succeeds, both parties have the same shared secret.
     ///
     /// # Arguments
-    /// * `server_send` - Server's response message (must be exactly 768 bytes)
+    /// * `server_send` - Server's response message (must be exactly `SERVER_SEND_KEY_SIZE` bytes)
     ///
     /// # Returns
     /// * `Ok(())` - Protocol completed successfully, shared secret is available
@@ -360,8 +819,9 @@ impl KyberModule {
     pub fn client_confirm(&mut self, server_send: Vec<u8>) -> Result<(), Errors> {
         if server_send.len() != Self::SERVER_SEND_KEY_SIZE {
             return Err(Errors::KyberError(format!(
-                "Server message must be {} bytes long, got {}",
+                "Server message must be {} bytes long for {}, got {}",
                 Self::SERVER_SEND_KEY_SIZE,
+                KyberLevel::CURRENT,
                 server_send.len()
             )));
         }
@@ -376,6 +836,152 @@ impl KyberModule {
             .map_err(|e| Errors::KyberError(e.to_string()))?)
     }
 
+    /// Initiates mutually authenticated key exchange (AKE) as a client.
+    ///
+    /// Unlike UAKE, both static keys take part: the server must know this module's public key.
+    /// The handshake state lives in the returned `AkeSession`, so one module can run
+    /// any number of handshakes at once.
+    ///
+    /// # Arguments
+    /// * `server_public` - Server's static public key
+    ///
+    /// # Returns
+    /// * `Ok((AkeSession, Vec<u8>))` - Session to confirm later and the message (`AKE_SEND_A_SIZE` bytes) to send
+    /// * `Err(Errors)` - Invalid public key size or protocol error
+    ///
+    /// # Example
+    /// ```rust
+    /// # use crystalyst_rs::utils::kyber::KyberModule;
+    /// # fn main() -> Result<(), crystalyst_rs::Errors> {
+    /// # let client = KyberModule::new()?;
+    /// # let server = KyberModule::new()?;
+    /// let (mut session, send_a) = client.ake_client_init(&server.public)?;
+    /// // Send send_a to the server, which answers with `ake_server_receive`
+    /// # let (_, server_send) = server.ake_server_receive(&client.public, &send_a)?;
+    /// client.ake_client_confirm(&mut session, &server_send)?;
+    /// let secret = session.shared_secret()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ake_client_init(
+        &self,
+        server_public: &KyberPublicKey,
+    ) -> Result<(AkeSession, Vec<u8>), Errors> {
+        let server_public = fixed_size(server_public.as_bytes(), "Public key")?;
+
+        let mut ake = Ake::new();
+        let send_a = ake
+            .client_init(server_public, &mut OsRng)
+            .map_err(|e| Errors::KyberError(e.to_string()))?;
+
+        Ok((
+            AkeSession {
+                ake: SecretBox::new(Box::new(ake)),
+                established: false,
+            },
+            send_a.to_vec(),
+        ))
+    }
+
+    /// Like `ake_client_init`, after checking `server_public` against `trust` for peer `server`.
+    ///
+    /// # Errors
+    /// Returns `Errors::UntrustedKey` if the store rejects the key.
+    pub fn ake_client_init_verified(
+        &self,
+        server: &str,
+        server_public: &KyberPublicKey,
+        trust: &mut TrustStore,
+    ) -> Result<(AkeSession, Vec<u8>), Errors> {
+        trust.check(server, server_public)?;
+        self.ake_client_init(server_public)
+    }
+
+    /// Answers a client's AKE message as a server.
+    ///
+    /// The session is established once this returns, the shared secret only matches the
+    /// client's if `client_public` really belongs to the client and the client holds
+    /// the private key of this module's public key.
+    ///
+    /// # Arguments
+    /// * `client_public` - Client's static public key
+    /// * `send_a` - Client's initial message (must be exactly `AKE_SEND_A_SIZE` bytes)
+    ///
+    /// # Returns
+    /// * `Ok((AkeSession, Vec<u8>))` - Established session and the response (`AKE_SERVER_SEND_SIZE` bytes) to send back
+    /// * `Err(Errors)` - Invalid key or message size, or protocol error
+    ///
+    /// # Example
+    /// ```rust
+    /// # use crystalyst_rs::utils::kyber::KyberModule;
+    /// # fn main() -> Result<(), crystalyst_rs::Errors> {
+    /// # let client = KyberModule::new()?;
+    /// # let server = KyberModule::new()?;
+    /// # let client_public = client.public.clone();
+    /// # let (_, send_a) = client.ake_client_init(&server.public)?;
+    /// let (session, server_send) = server.ake_server_receive(&client_public, &send_a)?;
+    /// // Send server_send back to the client
+    /// let secret = session.shared_secret()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ake_server_receive(
+        &self,
+        client_public: &KyberPublicKey,
+        send_a: &[u8],
+    ) -> Result<(AkeSession, Vec<u8>), Errors> {
+        let client_public = fixed_size(client_public.as_bytes(), "Public key")?;
+        let send_a = fixed_size(send_a, "Client message")?;
+        let secret = fixed_size(self.secret.expose_secret(), "Private key")?;
+
+        let mut ake = Ake::new();
+        let server_send = ake
+            .server_receive(*send_a, client_public, secret, &mut OsRng)
+            .map_err(|e| Errors::KyberError(e.to_string()))?;
+
+        Ok((
+            AkeSession {
+                ake: SecretBox::new(Box::new(ake)),
+                established: true,
+            },
+            server_send.to_vec(),
+        ))
+    }
+
+    /// Completes an AKE handshake started with `ake_client_init`.
+    /// - Must be called on the module that started the session.
+    ///
+    /// # Arguments
+    /// * `session` - Session returned by `ake_client_init`
+    /// * `server_send` - Server's response (must be exactly `AKE_SERVER_SEND_SIZE` bytes)
+    ///
+    /// # Returns
+    /// * `Ok(())` - Handshake completed, `session.shared_secret()` is available
+    /// * `Err(Errors)` - Session already established, invalid message size or protocol error
+    pub fn ake_client_confirm(
+        &self,
+        session: &mut AkeSession,
+        server_send: &[u8],
+    ) -> Result<(), Errors> {
+        if session.established {
+            return Err(Errors::KyberError(
+                "AKE session is already established".to_string(),
+            ));
+        }
+
+        let server_send = fixed_size(server_send, "Server message")?;
+        let secret = fixed_size(self.secret.expose_secret(), "Private key")?;
+
+        session
+            .ake
+            .expose_secret_mut()
+            .client_confirm(*server_send, secret)
+            .map_err(|e| Errors::KyberError(e.to_string()))?;
+        session.established = true;
+
+        Ok(())
+    }
+
     /// Performs Kyber key encapsulation.
     ///
     /// This generates a random shared secret and encapsulates it using
@@ -469,3 +1075,55 @@ impl KyberModule {
             && self.secret.expose_secret().len() == Self::SECRET_KEY_SIZE
     }
 }
+
+#[cfg(feature = "pqc_kyber")]
+/// State of one mutually authenticated key exchange.
+///
+/// Created by `KyberModule::ake_client_init` or `KyberModule::ake_server_receive`.
+/// Sessions only borrow the long-term keypair, so they can be moved to other threads
+/// while the same `KyberModule` keeps serving new handshakes.
+///
+/// Authentication is implicit: a peer using the wrong keys ends up with a different
+/// shared secret, which shows up as the first message failing to decrypt.
+pub struct AkeSession {
+    ake: SecretBox<Ake>,
+    established: bool,
+}
+
+#[cfg(feature = "pqc_kyber")]
+impl AkeSession {
+    /// Returns `true` once the handshake has completed on this side.
+    pub fn is_established(&self) -> bool {
+        self.established
+    }
+
+    /// Retrieves the shared secret of the completed handshake.
+    ///
+    /// # Errors
+    /// Returns `Errors::KyberError` if the client has not called `ake_client_confirm` yet.
+    pub fn shared_secret(&self) -> Result<SharedSecret, Errors> {
+        if !self.established {
+            return Err(Errors::KyberError(
+                "AKE session is not established yet".to_string(),
+            ));
+        }
+
+        Ok(SharedSecret::new(
+            self.ake.expose_secret().shared_secret.to_vec(),
+        ))
+    }
+}
+
+/// Borrows `data` as a fixed size array, naming `what` in the error.
+#[cfg(feature = "pqc_kyber")]
+fn fixed_size<'a, const N: usize>(data: &'a [u8], what: &str) -> Result<&'a [u8; N], Errors> {
+    data.try_into().map_err(|_| {
+        Errors::KyberError(format!(
+            "{} must be {} bytes long for {}, got {}",
+            what,
+            N,
+            KyberLevel::CURRENT,
+            data.len()
+        ))
+    })
+}
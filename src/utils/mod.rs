@@ -1,4 +1,7 @@
 pub mod base_utils;
 pub mod calculate;
-#[cfg(any(feature = "kyber", doc))]
+pub mod key_cache;
+#[cfg(any(feature = "pqc_kyber", doc))]
 pub mod kyber;
+#[cfg(feature = "pqc_kyber")]
+pub mod trust;
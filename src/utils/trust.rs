@@ -0,0 +1,309 @@
+//! # Key Fingerprints and Trust Store
+//!
+//! `Fingerprint` is a SHA3-256 digest of a Kyber public key, short enough to compare by eye
+//! or read out loud as words from the recovery wordlist.
+//!
+//! `TrustStore` remembers which key belongs to which peer name, similar to SSH `known_hosts`:
+//! - **Pinned**: first key seen for a name (trust on first use), later keys must match it.
+//! - **Trusted**: key explicitly approved with `trust`, e.g. after comparing fingerprints.
+//! - **Revoked**: key rejected for every name, even if it was pinned or trusted.
+//!
+//! ## Text Format
+//! One entry per line, `#` starts a comment:
+//! ```text
+//! trusted <hex fingerprint> <name>
+//! pinned <hex fingerprint> <name>
+//! revoked <hex fingerprint>
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sha3::{Digest, Sha3_256};
+
+use crate::{Errors, RECOVERY_WORDS, lookup_recovery_word, utils::kyber::KyberPublicKey};
+
+/// Fingerprint length in bytes.
+pub const FINGERPRINT_LEN: usize = 32;
+/// Number of words in the word rendering of a fingerprint.
+pub const FINGERPRINT_WORDS: usize = 12;
+
+/// SHA3-256 fingerprint of a Kyber public key.
+/// - Covers the key's binary encoding, so the same key material at another Kyber level
+///   has a different fingerprint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fingerprint([u8; FINGERPRINT_LEN]);
+
+impl Fingerprint {
+    /// Computes the fingerprint of `key`.
+    pub fn of(key: &KyberPublicKey) -> Self {
+        let mut hash = Sha3_256::new();
+        hash.update(b"CRYSTALYST-kyber-fingerprint");
+        hash.update(key.to_bytes());
+        Self(hash.finalize().into())
+    }
+
+    /// Returns the raw fingerprint bytes.
+    pub fn as_bytes(&self) -> &[u8; FINGERPRINT_LEN] {
+        &self.0
+    }
+
+    /// Renders the full fingerprint as lowercase hex.
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Parses a hex fingerprint, `:` separators and whitespace are ignored.
+    ///
+    /// # Errors
+    /// Returns `Errors::UntrustedKey` if `text` is not a 32-byte hex string.
+    pub fn from_hex(text: &str) -> Result<Self, Errors> {
+        let digits: Vec<u8> = text
+            .bytes()
+            .filter(|c| *c != b':' && !c.is_ascii_whitespace())
+            .collect();
+        if digits.len() != FINGERPRINT_LEN * 2 {
+            return Err(Errors::UntrustedKey(format!(
+                "Fingerprint must be {} hex digits, got {}",
+                FINGERPRINT_LEN * 2,
+                digits.len()
+            )));
+        }
+
+        let mut out = [0u8; FINGERPRINT_LEN];
+        for (byte, pair) in out.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).unwrap_or("");
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| {
+                Errors::UntrustedKey(format!("Invalid hex in fingerprint: {:?}", pair))
+            })?;
+        }
+
+        Ok(Self(out))
+    }
+
+    /// Renders the first `FINGERPRINT_WORDS` bytes as words, e.g. `APPLE-ORBIT-...`.
+    /// - Meant for comparing keys over the phone or side by side, not for storage.
+    pub fn to_words(&self) -> String {
+        self.0[..FINGERPRINT_WORDS]
+            .iter()
+            .map(|b| RECOVERY_WORDS[*b as usize])
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Checks words read back by a person against this fingerprint.
+    /// - Case-insensitive, words may be separated by `-` or whitespace.
+    /// - Like recovery phrases, the first 3 letters of every word are enough.
+    pub fn matches_words(&self, words: &str) -> bool {
+        let indices: Vec<Option<u8>> = words
+            .split(|c: char| c == '-' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .map(lookup_recovery_word)
+            .collect();
+
+        indices.len() == FINGERPRINT_WORDS
+            && indices
+                .iter()
+                .zip(&self.0)
+                .all(|(index, byte)| *index == Some(*byte))
+    }
+}
+
+impl std::fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// How a peer's key became known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustState {
+    /// Pinned on first use.
+    Pinned,
+    /// Explicitly trusted.
+    Trusted,
+}
+
+/// Local store of peer keys with TOFU pinning, explicit trust and revocation.
+/// - Use `check` before every handshake, or `KyberModule::client_init_verified`.
+/// - Persist it with `to_text` and `from_text`, the store never touches the file system itself.
+#[derive(Debug, Clone)]
+pub struct TrustStore {
+    peers: BTreeMap<String, (Fingerprint, TrustState)>,
+    revoked: BTreeSet<Fingerprint>,
+    tofu: bool,
+}
+
+impl Default for TrustStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrustStore {
+    /// Creates an empty store with trust on first use enabled.
+    pub fn new() -> Self {
+        Self {
+            peers: BTreeMap::new(),
+            revoked: BTreeSet::new(),
+            tofu: true,
+        }
+    }
+
+    /// Enables or disables trust on first use.
+    /// - When disabled, `check` rejects every name that was not added with `trust`.
+    pub fn tofu(mut self, enabled: bool) -> Self {
+        self.tofu = enabled;
+        self
+    }
+
+    /// Checks `key` for peer `name`, pinning it if the name is unknown and TOFU is enabled.
+    ///
+    /// # Returns
+    /// How the key is trusted.
+    ///
+    /// # Errors
+    /// Returns `Errors::UntrustedKey` if the key is revoked, differs from the known key of `name`,
+    /// or `name` is unknown and TOFU is disabled.
+    pub fn check(&mut self, name: &str, key: &KyberPublicKey) -> Result<TrustState, Errors> {
+        check_name(name)?;
+        let fingerprint = key.fingerprint();
+
+        if self.revoked.contains(&fingerprint) {
+            return Err(Errors::UntrustedKey(format!(
+                "Key {} of {} is revoked",
+                fingerprint, name
+            )));
+        }
+
+        match self.peers.get(name) {
+            Some((known, state)) if *known == fingerprint => Ok(*state),
+            Some((known, _)) => Err(Errors::UntrustedKey(format!(
+                "Key of {} changed, expected {}, got {}",
+                name, known, fingerprint
+            ))),
+            None if self.tofu => {
+                self.peers
+                    .insert(name.to_string(), (fingerprint, TrustState::Pinned));
+                Ok(TrustState::Pinned)
+            }
+            None => Err(Errors::UntrustedKey(format!("{} is not trusted", name))),
+        }
+    }
+
+    /// Trusts `key` for `name`, replacing any pinned or trusted key of that name.
+    ///
+    /// # Errors
+    /// Returns `Errors::UntrustedKey` if the key is revoked.
+    pub fn trust(&mut self, name: &str, key: &KyberPublicKey) -> Result<(), Errors> {
+        self.trust_fingerprint(name, key.fingerprint())
+    }
+
+    /// Like `trust`, for a fingerprint obtained out of band.
+    pub fn trust_fingerprint(
+        &mut self,
+        name: &str,
+        fingerprint: Fingerprint,
+    ) -> Result<(), Errors> {
+        check_name(name)?;
+        if self.revoked.contains(&fingerprint) {
+            return Err(Errors::UntrustedKey(format!(
+                "Key {} is revoked",
+                fingerprint
+            )));
+        }
+
+        self.peers
+            .insert(name.to_string(), (fingerprint, TrustState::Trusted));
+        Ok(())
+    }
+
+    /// Revokes a key for every name.
+    /// - Names using it stay bound to it, so a replacement key has to be trusted explicitly.
+    pub fn revoke(&mut self, fingerprint: Fingerprint) {
+        self.revoked.insert(fingerprint);
+    }
+
+    /// Returns `true` if `fingerprint` was revoked.
+    pub fn is_revoked(&self, fingerprint: &Fingerprint) -> bool {
+        self.revoked.contains(fingerprint)
+    }
+
+    /// Returns the known key of `name` and how it is trusted.
+    pub fn get(&self, name: &str) -> Option<(Fingerprint, TrustState)> {
+        self.peers.get(name).copied()
+    }
+
+    /// Removes `name`, the next `check` pins its key again if TOFU is enabled.
+    ///
+    /// # Returns
+    /// `true` if `name` was known.
+    pub fn forget(&mut self, name: &str) -> bool {
+        self.peers.remove(name).is_some()
+    }
+
+    /// Serializes the store in the text format described in the module docs.
+    pub fn to_text(&self) -> String {
+        let mut out = String::from("# CRYSTALYST trust store\n");
+        for (name, (fingerprint, state)) in &self.peers {
+            let state = match state {
+                TrustState::Pinned => "pinned",
+                TrustState::Trusted => "trusted",
+            };
+            out.push_str(&format!("{} {} {}\n", state, fingerprint, name));
+        }
+        for fingerprint in &self.revoked {
+            out.push_str(&format!("revoked {}\n", fingerprint));
+        }
+        out
+    }
+
+    /// Parses a store written by `to_text`, TOFU is enabled.
+    ///
+    /// # Errors
+    /// Returns `Errors::UntrustedKey` naming the first malformed line.
+    pub fn from_text(text: &str) -> Result<Self, Errors> {
+        let mut store = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let malformed = || Errors::UntrustedKey(format!("Malformed line {}", number + 1));
+            let (kind, rest) = line.split_once(' ').ok_or_else(malformed)?;
+            let (fingerprint, name) = match rest.split_once(' ') {
+                Some((fingerprint, name)) => (fingerprint, Some(name.trim())),
+                None => (rest, None),
+            };
+            let fingerprint = Fingerprint::from_hex(fingerprint).map_err(|_| malformed())?;
+
+            let state = match (kind, name) {
+                ("revoked", None) => {
+                    store.revoked.insert(fingerprint);
+                    continue;
+                }
+                ("pinned", Some(_)) => TrustState::Pinned,
+                ("trusted", Some(_)) => TrustState::Trusted,
+                _ => return Err(malformed()),
+            };
+            let name = name.unwrap_or_default();
+            check_name(name).map_err(|_| malformed())?;
+            store.peers.insert(name.to_string(), (fingerprint, state));
+        }
+
+        Ok(store)
+    }
+}
+
+/// Names are stored one per line, so they must be non-empty and single-line.
+fn check_name(name: &str) -> Result<(), Errors> {
+    if name.trim().is_empty() || name.contains(['\n', '\r']) || name != name.trim() {
+        return Err(Errors::UntrustedKey(format!(
+            "Invalid peer name: {:?}",
+            name
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,302 @@
+#![cfg(all(feature = "async", feature = "key_derivation"))]
+
+use std::{
+    collections::VecDeque,
+    future::{Future, poll_fn},
+    io::{self, Write},
+    pin::{Pin, pin},
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+use crystalyst_rs::{
+    Config, ThreadStrategy,
+    cipher::stream_cipher::CrystalystStream,
+    profiles,
+    rng_utils::{
+        nonce::{Nonce, NonceType},
+        rng::RNG,
+    },
+};
+use futures_io::{AsyncRead, AsyncWrite};
+
+/// Wakes the thread blocked in `block_on`.
+struct ThreadWaker {
+    woken: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        *self.woken.lock().unwrap() = true;
+        self.cond.notify_one();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Arc::new(ThreadWaker {
+        woken: Mutex::new(false),
+        cond: Condvar::new(),
+    });
+    let task_waker = Waker::from(Arc::clone(&waker));
+    let mut cx = Context::from_waker(&task_waker);
+
+    loop {
+        if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+            return out;
+        }
+
+        let mut woken = waker.woken.lock().unwrap();
+        while !*woken {
+            woken = waker.cond.wait(woken).unwrap();
+        }
+        *woken = false;
+    }
+}
+
+#[derive(Default)]
+struct PipeState {
+    data: VecDeque<u8>,
+    closed: bool,
+    reader: Option<Waker>,
+    writer: Option<Waker>,
+}
+
+/// One direction of an in-memory duplex, holding at most `capacity` bytes so both sides hit `Pending`.
+#[derive(Clone)]
+struct Pipe {
+    state: Arc<Mutex<PipeState>>,
+    capacity: usize,
+}
+
+impl Pipe {
+    fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::default(),
+            capacity,
+        }
+    }
+}
+
+impl AsyncWrite for Pipe {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut state = self.state.lock().unwrap();
+        let n = buf.len().min(self.capacity - state.data.len());
+        if n == 0 {
+            state.writer = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        state.data.extend(&buf[..n]);
+        if let Some(waker) = state.reader.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        if let Some(waker) = state.reader.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for Pipe {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut state = self.state.lock().unwrap();
+        if state.data.is_empty() {
+            if state.closed {
+                return Poll::Ready(Ok(0));
+            }
+            state.reader = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let n = buf.len().min(state.data.len());
+        for (byte, value) in buf.iter_mut().zip(state.data.drain(..n)) {
+            *byte = value;
+        }
+        if let Some(waker) = state.writer.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        let n = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, buf)).await?;
+        buf = &buf[n..];
+    }
+    Ok(())
+}
+
+async fn close<W: AsyncWrite + Unpin>(writer: &mut W) -> io::Result<()> {
+    poll_fn(|cx| Pin::new(&mut *writer).poll_close(cx)).await
+}
+
+async fn read_to_end<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buf = [0u8; 3000];
+    loop {
+        let n = poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, &mut buf)).await?;
+        if n == 0 {
+            return Ok(out);
+        }
+        out.extend_from_slice(&buf[..n]);
+    }
+}
+
+fn stream(config: Config) -> CrystalystStream {
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+    CrystalystStream::new(config, &[0x42; 32], nonce).chunk_size(4096)
+}
+
+/// Encrypts on one thread and decrypts on another over the duplex.
+fn duplex_round_trip(config: Config, data: &[u8]) -> Vec<u8> {
+    let pipe = Pipe::new(1500);
+    let sender = stream(config);
+    let receiver = stream(config);
+
+    let mut writer = sender.async_writer(pipe.clone()).unwrap();
+    let input = data.to_vec();
+    let writing = thread::spawn(move || {
+        block_on(async {
+            write_all(&mut writer, &input).await?;
+            close(&mut writer).await
+        })
+    });
+
+    let mut reader = receiver.async_reader(pipe).unwrap();
+    let out = block_on(read_to_end(&mut reader)).unwrap();
+    writing.join().unwrap().unwrap();
+    out
+}
+
+#[test]
+fn async_duplex_round_trip() {
+    let config = profiles::FAST.key_derivation(false);
+    for len in [0, 1, 4095, 4096, 4097, 3 * 4096 + 17] {
+        let data: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+        assert_eq!(duplex_round_trip(config, &data), data, "length {}", len);
+    }
+}
+
+#[test]
+fn async_round_trip_respects_thread_strategy() {
+    let data: Vec<u8> = (0..20_000).map(|i| (i % 251) as u8).collect();
+    for strategy in [ThreadStrategy::SingleThread, ThreadStrategy::Custom(3)] {
+        let config = profiles::FAST.key_derivation(false).set_thread(strategy);
+        assert_eq!(duplex_round_trip(config, &data), data);
+    }
+}
+
+#[test]
+fn async_reader_matches_sync_format() {
+    let config = profiles::FAST.key_derivation(false);
+    let data: Vec<u8> = (0..10_000).map(|i| (i * 13) as u8).collect();
+    let stream = stream(config);
+
+    let mut writer = stream.writer(Vec::new()).unwrap();
+    writer.write_all(&data).unwrap();
+    let encrypted = writer.finish().unwrap();
+
+    let pipe = Pipe::new(encrypted.len().max(1));
+    let mut feed = pipe.clone();
+    block_on(async {
+        write_all(&mut feed, &encrypted).await.unwrap();
+        close(&mut feed).await.unwrap();
+    });
+
+    let mut reader = stream.async_reader(pipe).unwrap();
+    assert_eq!(block_on(read_to_end(&mut reader)).unwrap(), data);
+}
+
+#[test]
+fn async_reader_rejects_tampered_stream() {
+    let config = profiles::FAST.key_derivation(false);
+    let stream = stream(config);
+
+    let mut writer = stream.writer(Vec::new()).unwrap();
+    writer.write_all(&[0x11; 5000]).unwrap();
+    let mut encrypted = writer.finish().unwrap();
+    let last = encrypted.len() - 1;
+    encrypted[last] ^= 1;
+
+    let pipe = Pipe::new(encrypted.len());
+    let mut feed = pipe.clone();
+    block_on(async {
+        write_all(&mut feed, &encrypted).await.unwrap();
+        close(&mut feed).await.unwrap();
+    });
+
+    let mut reader = stream.async_reader(pipe).unwrap();
+    assert!(block_on(read_to_end(&mut reader)).is_err());
+}
+
+/// Accepts everything except its `fail_at`-th write, which fails once.
+struct FlakyWriter {
+    writes: usize,
+    fail_at: usize,
+    out: Vec<u8>,
+}
+
+impl AsyncWrite for FlakyWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.writes += 1;
+        if self.writes == self.fail_at {
+            return Poll::Ready(Err(io::Error::other("disk full")));
+        }
+
+        self.out.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn async_writer_fails_after_an_inner_error() {
+    let config = profiles::FAST.key_derivation(false);
+    let inner = FlakyWriter {
+        writes: 0,
+        fail_at: 2,
+        out: Vec::new(),
+    };
+    let mut writer = stream(config).async_writer(inner).unwrap();
+
+    // The header is the first write, the first chunk the second one.
+    let data = [0x11; 3 * 4096];
+    assert!(block_on(write_all(&mut writer, &data)).is_err());
+    assert!(block_on(write_all(&mut writer, &data)).is_err());
+    assert!(block_on(poll_fn(|cx| Pin::new(&mut writer).poll_flush(cx))).is_err());
+    assert!(block_on(close(&mut writer)).is_err());
+    assert_eq!(writer.into_inner().writes, 2);
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "key_derivation")]
+
+use crystalyst_rs::{
+    Config, Errors, RecoveryKey,
+    cipher::{
+        block_cipher::{CrystalystBuilder, Utils},
+        header::Header,
+    },
+    profiles,
+    rng_utils::{
+        nonce::{Nonce, NonceType},
+        rng::RNG,
+    },
+};
+
+const PASSWORD: &[u8] = &[0x42; 32];
+const NEW_PASSWORD: &[u8] = &[0x24; 32];
+
+fn data() -> Vec<u8> {
+    (0..300).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+/// Encrypts `data` under `config` with nonce and settings wrapped into the output.
+fn encrypt(config: Config, data: &[u8]) -> Vec<u8> {
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+    let mut out = Vec::new();
+    CrystalystBuilder::new()
+        .data(data)
+        .password(PASSWORD)
+        .nonce(nonce)
+        .config(config)
+        .utils(Utils::new().wrap_all(true))
+        .encrypt(&mut out)
+        .unwrap();
+    out
+}
+
+fn decrypt(encrypted: &[u8]) -> Result<Vec<u8>, Errors> {
+    let mut out = Vec::new();
+    CrystalystBuilder::new()
+        .data(encrypted)
+        .password(PASSWORD)
+        .decrypt(&mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn decrypts_with_only_the_password() {
+    let data = data();
+    for config in [profiles::FAST, profiles::SECURE, profiles::FORTRESS] {
+        let encrypted = encrypt(config.key_derivation(false), &data);
+        assert_eq!(decrypt(&encrypted).unwrap(), data);
+    }
+}
+
+#[test]
+fn rejects_tampered_header() {
+    let data = data();
+    let encrypted = encrypt(profiles::FAST.key_derivation(false), &data);
+    let (_, header_len) = Header::parse(&encrypted).unwrap();
+
+    for i in 0..header_len {
+        let mut tampered = encrypted.clone();
+        tampered[i] ^= 0x10;
+        assert!(decrypt(&tampered).is_err(), "flipped header byte {}", i);
+    }
+}
+
+#[test]
+fn associated_data_must_match() {
+    let data = data();
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+    let mut encrypted = Vec::new();
+    CrystalystBuilder::new()
+        .data(&data)
+        .password(PASSWORD)
+        .nonce(nonce)
+        .associated_data(b"row 17")
+        .config(profiles::FAST.key_derivation(false))
+        .utils(Utils::new().wrap_all(true))
+        .encrypt(&mut encrypted)
+        .unwrap();
+
+    let decrypt_with = |associated_data: &[u8]| {
+        let mut out = Vec::new();
+        CrystalystBuilder::new()
+            .data(&encrypted)
+            .password(PASSWORD)
+            .associated_data(associated_data)
+            .decrypt(&mut out)
+            .map(|_| out)
+    };
+
+    assert_eq!(decrypt_with(b"row 17").unwrap(), data);
+    for other in [&b"row 18"[..], b"row 1", b""] {
+        assert!(matches!(decrypt_with(other), Err(Errors::InvalidMac(_))));
+    }
+    assert!(decrypt(&encrypted).is_err());
+}
+
+/// Replaces the second letter of word `position` so it is no longer in the wordlist.
+fn mistype(phrase: &str, position: usize) -> String {
+    let mut words: Vec<String> = phrase.split('-').map(str::to_string).collect();
+    let word = &mut words[position];
+    let typo = if word.as_bytes()[1] == b'Q' { "X" } else { "Q" };
+    word.replace_range(1..2, typo);
+    words.join("-")
+}
+
+#[test]
+fn recovery_key_survives_one_mistyped_word() {
+    let data = data();
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+    let mut encrypted = Vec::new();
+    let recovery_key = CrystalystBuilder::new()
+        .data(&data)
+        .password(PASSWORD)
+        .nonce(nonce)
+        .config(profiles::FAST.key_derivation(false))
+        .utils(Utils::new().wrap_all(true).recovery_key(true))
+        .encrypt(&mut encrypted)
+        .unwrap()
+        .unwrap();
+    let phrase = recovery_key.expose_secret();
+    let word_count = phrase.split('-').count();
+
+    let decrypt_with = |phrase: &str| {
+        let mut out = Vec::new();
+        CrystalystBuilder::new()
+            .data(&encrypted)
+            .decrypt_with_recovery_key(phrase, &mut out)
+            .map(|_| out)
+    };
+
+    assert_eq!(decrypt_with(phrase).unwrap(), data);
+    // Data words and checksum words alike.
+    for position in [0, 17, word_count - 1] {
+        let typo = mistype(phrase, position);
+        RecoveryKey::verify(&typo).unwrap();
+        assert_eq!(decrypt_with(&typo).unwrap(), data, "word {}", position);
+    }
+
+    let two_typos = mistype(&mistype(phrase, 3), 9);
+    assert!(matches!(
+        RecoveryKey::verify(&two_typos),
+        Err(Errors::InvalidRecoveryKey(_))
+    ));
+    assert!(matches!(
+        decrypt_with(&two_typos),
+        Err(Errors::InvalidRecoveryKey(_))
+    ));
+}
+
+#[test]
+fn rekey_moves_data_to_the_new_password() {
+    let data = data();
+    let encrypted = encrypt(profiles::FAST.key_derivation(false), &data);
+    let rekey = |old_password: &[u8], out: &mut Vec<u8>| {
+        CrystalystBuilder::new()
+            .data(&encrypted)
+            .rekey(old_password, NEW_PASSWORD, out)
+    };
+
+    let mut rekeyed = Vec::new();
+    assert!(rekey(PASSWORD, &mut rekeyed).unwrap().is_none());
+    assert_ne!(rekeyed, encrypted);
+    assert!(decrypt(&rekeyed).is_err());
+
+    let mut out = Vec::new();
+    CrystalystBuilder::new()
+        .data(&rekeyed)
+        .password(NEW_PASSWORD)
+        .decrypt(&mut out)
+        .unwrap();
+    assert_eq!(out, data);
+
+    let mut out = vec![1, 2, 3];
+    assert!(rekey(NEW_PASSWORD, &mut out).is_err());
+    assert!(out.is_empty());
+}
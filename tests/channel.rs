@@ -0,0 +1,251 @@
+#![cfg(all(feature = "pqc_kyber", unix))]
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    thread,
+};
+
+use crystalyst_rs::{
+    Config, Errors,
+    cipher::stream_cipher::{CrystalystChannel, MAX_RECORD_SIZE, TAG_SIZE},
+    profiles,
+    utils::kyber::KyberModule,
+};
+
+/// Length, sequence number and flags in front of every record.
+const RECORD_HEADER_LEN: usize = 4 + 8 + 1;
+
+type Channel = CrystalystChannel<UnixStream>;
+
+fn config() -> Config {
+    profiles::FAST
+}
+
+/// Runs `server` on its own thread and `client` on this one over a fresh socket pair.
+fn handshake<C, S>(client: C, server: S) -> (Result<Channel, Errors>, Result<Channel, Errors>)
+where
+    C: FnOnce(UnixStream) -> Result<Channel, Errors>,
+    S: FnOnce(UnixStream) -> Result<Channel, Errors> + Send + 'static,
+{
+    let (client_stream, server_stream) = UnixStream::pair().unwrap();
+    let accepting = thread::spawn(move || server(server_stream));
+    let client = client(client_stream);
+    (client, accepting.join().unwrap())
+}
+
+/// UAKE channel plus raw handles on both socket ends to capture and inject client records.
+fn uake_with_taps() -> (Channel, Channel, UnixStream, UnixStream) {
+    let server_identity = KyberModule::new().unwrap();
+    let server_public = server_identity.public.clone();
+
+    let (client_stream, server_stream) = UnixStream::pair().unwrap();
+    let inject = client_stream.try_clone().unwrap();
+    let capture = server_stream.try_clone().unwrap();
+
+    let accepting =
+        thread::spawn(move || Channel::accept(server_stream, &server_identity, config()));
+    let client = Channel::connect(client_stream, &server_public, config()).unwrap();
+    let server = accepting.join().unwrap().unwrap();
+    (client, server, inject, capture)
+}
+
+/// Sends `data` from `client` and takes the raw record off the wire before the server sees it.
+fn capture_record(client: &mut Channel, capture: &mut UnixStream, data: &[u8]) -> Vec<u8> {
+    client.send(data).unwrap();
+    let mut record = vec![0u8; RECORD_HEADER_LEN + data.len() + TAG_SIZE];
+    capture.read_exact(&mut record).unwrap();
+    record
+}
+
+fn exchange(client: &mut Channel, server: &mut Channel) {
+    let mut buffer = Vec::new();
+    let big: Vec<u8> = (0..MAX_RECORD_SIZE).map(|i| (i % 253) as u8).collect();
+
+    for message in [&b"hello"[..], &[], &big] {
+        // A full record does not fit the socket buffer, so the receiver runs alongside.
+        thread::scope(|scope| {
+            scope.spawn(|| client.send(message).unwrap());
+            assert!(server.recv(&mut buffer).unwrap());
+        });
+        assert_eq!(buffer, message);
+
+        thread::scope(|scope| {
+            scope.spawn(|| server.send(message).unwrap());
+            assert!(client.recv(&mut buffer).unwrap());
+        });
+        assert_eq!(buffer, message);
+    }
+}
+
+#[test]
+fn uake_round_trip() {
+    let server_identity = KyberModule::new().unwrap();
+    let server_public = server_identity.public.clone();
+
+    let (client, server) = handshake(
+        |stream| Channel::connect(stream, &server_public, config()),
+        move |stream| Channel::accept(stream, &server_identity, config()),
+    );
+    let (mut client, mut server) = (client.unwrap(), server.unwrap());
+
+    assert_eq!(client.peer_public(), Some(&server_public));
+    assert!(server.peer_public().is_none());
+    exchange(&mut client, &mut server);
+
+    client.close().unwrap();
+    let mut buffer = Vec::new();
+    assert!(!server.recv(&mut buffer).unwrap());
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn ake_round_trip() {
+    let server_identity = KyberModule::new().unwrap();
+    let server_public = server_identity.public.clone();
+    let client_identity = KyberModule::new().unwrap();
+    let client_keys = vec![
+        KyberModule::new().unwrap().public,
+        client_identity.public.clone(),
+    ];
+
+    let (client, server) = handshake(
+        |stream| Channel::connect_mutual(stream, &client_identity, &server_public, config()),
+        move |stream| Channel::accept_mutual(stream, &server_identity, &client_keys, config()),
+    );
+    let (mut client, mut server) = (client.unwrap(), server.unwrap());
+
+    assert_eq!(client.peer_public(), Some(&server_public));
+    assert_eq!(server.peer_public(), Some(&client_identity.public));
+    exchange(&mut client, &mut server);
+
+    server.close().unwrap();
+    assert!(!client.recv(&mut Vec::new()).unwrap());
+}
+
+#[test]
+fn ake_rejects_unknown_client_key() {
+    let server_identity = KyberModule::new().unwrap();
+    let server_public = server_identity.public.clone();
+    let client_identity = KyberModule::new().unwrap();
+    let client_keys = vec![KyberModule::new().unwrap().public];
+
+    let (client, server) = handshake(
+        |stream| Channel::connect_mutual(stream, &client_identity, &server_public, config()),
+        move |stream| Channel::accept_mutual(stream, &server_identity, &client_keys, config()),
+    );
+
+    assert!(matches!(client, Err(Errors::ChannelError(_))));
+    assert!(matches!(server, Err(Errors::ChannelError(_))));
+}
+
+#[test]
+fn rejects_replayed_record() {
+    let (mut client, mut server, mut inject, mut capture) = uake_with_taps();
+    let record = capture_record(&mut client, &mut capture, b"pay once");
+
+    inject.write_all(&record).unwrap();
+    inject.write_all(&record).unwrap();
+
+    let mut buffer = Vec::new();
+    assert!(server.recv(&mut buffer).unwrap());
+    assert_eq!(buffer, b"pay once");
+    assert!(matches!(
+        server.recv(&mut buffer),
+        Err(Errors::ChannelError(_))
+    ));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn rejects_dropped_record() {
+    let (mut client, mut server, _inject, mut capture) = uake_with_taps();
+    capture_record(&mut client, &mut capture, b"lost");
+
+    client.send(b"next").unwrap();
+    let mut buffer = Vec::new();
+    assert!(matches!(
+        server.recv(&mut buffer),
+        Err(Errors::ChannelError(_))
+    ));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn rejects_reordered_records() {
+    let (mut client, mut server, mut inject, mut capture) = uake_with_taps();
+    let first = capture_record(&mut client, &mut capture, b"first");
+    let second = capture_record(&mut client, &mut capture, b"second");
+
+    inject.write_all(&second).unwrap();
+    inject.write_all(&first).unwrap();
+
+    let mut buffer = Vec::new();
+    assert!(matches!(
+        server.recv(&mut buffer),
+        Err(Errors::ChannelError(_))
+    ));
+
+    // The early record is consumed, the expected one is still accepted.
+    assert!(server.recv(&mut buffer).unwrap());
+    assert_eq!(buffer, b"first");
+}
+
+#[test]
+fn equal_records_encrypt_differently_at_any_distance() {
+    let (mut client, _server, _inject, mut capture) = uake_with_taps();
+    let payload = [0x33u8; 100];
+    let ciphertext = |record: &[u8]| record[RECORD_HEADER_LEN..record.len() - TAG_SIZE].to_vec();
+
+    let records: Vec<Vec<u8>> = (1..=257)
+        .map(|_| capture_record(&mut client, &mut capture, &payload))
+        .collect();
+    let (first, later) = (&records[0], &records[256]);
+
+    assert_eq!(first[4..12], 1u64.to_le_bytes());
+    assert_eq!(later[4..12], 257u64.to_le_bytes());
+    assert_ne!(ciphertext(first), ciphertext(later));
+}
+
+#[test]
+fn rejects_client_asking_for_more_rounds() {
+    let server_identity = KyberModule::new().unwrap();
+    let server_public = server_identity.public.clone();
+    let server_config = config();
+    let client_config = server_config.rounds(server_config.rounds + 1);
+
+    let (client, server) = handshake(
+        |stream| Channel::connect(stream, &server_public, client_config),
+        move |stream| Channel::accept(stream, &server_identity, server_config),
+    );
+
+    assert!(matches!(client, Err(Errors::ChannelError(_))));
+    assert!(matches!(server, Err(Errors::ChannelError(_))));
+}
+
+#[test]
+fn rejects_tampered_record() {
+    let (mut client, mut server, mut inject, mut capture) = uake_with_taps();
+    let mut record = capture_record(&mut client, &mut capture, b"do not touch");
+    record[RECORD_HEADER_LEN] ^= 1;
+
+    inject.write_all(&record).unwrap();
+    let mut buffer = Vec::new();
+    assert!(matches!(
+        server.recv(&mut buffer),
+        Err(Errors::InvalidMac(_))
+    ));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn detects_connection_ending_without_close() {
+    let (client, server, inject, capture) = uake_with_taps();
+    let mut server = server;
+    drop((client, inject, capture));
+
+    assert!(matches!(
+        server.recv(&mut Vec::new()),
+        Err(Errors::ChannelError(_))
+    ));
+}
@@ -0,0 +1,69 @@
+//! Each `differential_check` runs AVX2 on and off under every entry of `THREAD_STRATEGIES`.
+
+#![cfg(feature = "key_derivation")]
+
+use crystalyst_rs::{
+    cipher::reference::{
+        differential_check, differential_check_profiles, differential_check_shift_rows,
+    },
+    profiles,
+};
+
+/// Tile size of the fused engine's round phase.
+const TILE_SIZE: usize = 192 * 1024;
+/// Rounds restart their Galois field counter every chunk of this size.
+const ROUND_CHUNK_SIZE: usize = 1024 * 1024;
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 + 7) as u8).collect()
+}
+
+/// Runs every profile against the reference, naming the length on failure.
+fn check_profiles(len: usize) {
+    if let Err(e) = differential_check_profiles(&data(len)) {
+        panic!("length {}: {}", len, e);
+    }
+}
+
+/// Lengths on both sides of `boundary`.
+fn around(boundary: usize) -> [usize; 3] {
+    [boundary - 1, boundary, boundary + 1]
+}
+
+#[test]
+fn profiles_match_reference_around_block_boundaries() {
+    for len in [1, 15, 16, 17, 191, 192, 193, 4099] {
+        check_profiles(len);
+    }
+}
+
+#[test]
+fn profiles_match_reference_around_tile_boundaries() {
+    for len in around(TILE_SIZE) {
+        check_profiles(len);
+    }
+}
+
+// Takes minutes, run it with `cargo test --test differential -- --ignored`.
+#[test]
+#[ignore]
+fn profiles_match_reference_around_round_chunk_boundaries() {
+    for len in around(ROUND_CHUNK_SIZE) {
+        check_profiles(len);
+    }
+}
+
+#[test]
+fn fast_matches_reference_across_several_round_chunks() {
+    let len = 2 * ROUND_CHUNK_SIZE + TILE_SIZE + 17;
+    differential_check(profiles::FAST.key_derivation(false), &data(len)).unwrap();
+}
+
+#[test]
+fn shift_rows_matches_reference_at_unaligned_lengths() {
+    for len in (0..=80).chain([191, 192, 193, TILE_SIZE + 7]) {
+        if let Err(e) = differential_check_shift_rows(&data(len)) {
+            panic!("length {}: {}", len, e);
+        }
+    }
+}
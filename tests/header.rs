@@ -0,0 +1,65 @@
+use crystalyst_rs::{
+    Errors,
+    cipher::header::{HEADER_VERSION, Header},
+    profiles,
+};
+
+fn header_bytes() -> Vec<u8> {
+    Header::from_config(&profiles::FAST, None, None)
+        .unwrap()
+        .to_bytes()
+}
+
+fn is_invalid(data: &[u8]) -> bool {
+    matches!(Header::parse(data), Err(Errors::InvalidHeader(_)))
+}
+
+#[test]
+fn parses_its_own_encoding() {
+    let bytes = header_bytes();
+    let (header, len) = Header::parse(&bytes).unwrap();
+    assert_eq!(len, bytes.len());
+    assert_eq!(header.to_bytes(), bytes);
+}
+
+#[test]
+fn rejects_other_versions() {
+    for version in [0, 1, HEADER_VERSION + 1] {
+        let mut bytes = header_bytes();
+        bytes[4] = version;
+        assert!(is_invalid(&bytes), "version {}", version);
+    }
+}
+
+#[test]
+fn rejects_unknown_flags() {
+    for bit in 5..8 {
+        let mut bytes = header_bytes();
+        bytes[5] |= 1 << bit;
+        assert!(is_invalid(&bytes), "flag bit {}", bit);
+    }
+}
+
+#[cfg(feature = "key_derivation")]
+#[test]
+fn rejects_argon2_parameters_out_of_range() {
+    use crystalyst_rs::Argon2Params;
+
+    let key_derivation = profiles::FAST.key_derivation(true);
+    let bytes = Header::from_config(&key_derivation, None, None)
+        .unwrap()
+        .to_bytes();
+
+    let cases = [
+        (16, 0),
+        (16, Argon2Params::MAX_ITERATIONS + 1),
+        (20, 0),
+        (20, Argon2Params::MAX_LANES + 1),
+        (12, Argon2Params::MAX_MEMORY_COST + 1),
+    ];
+    for (offset, value) in cases {
+        let mut tampered = bytes.clone();
+        tampered[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        assert!(is_invalid(&tampered), "{} at offset {}", value, offset);
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "pqc_kyber")]
+
+use crystalyst_rs::{
+    Config, Errors,
+    cipher::kem::{self, MAX_RECIPIENTS},
+    profiles,
+    utils::kyber::KyberModule,
+};
+
+const MESSAGE: &[u8] = b"for every recipient on the list";
+
+fn config() -> Config {
+    profiles::FAST
+}
+
+fn seal(recipients: &[&KyberModule]) -> Result<Vec<u8>, Errors> {
+    let publics: Vec<_> = recipients.iter().map(|module| &module.public).collect();
+    let mut sealed = Vec::new();
+    kem::seal(&publics, MESSAGE, config(), &mut sealed)?;
+    Ok(sealed)
+}
+
+fn open(recipient: &KyberModule, sealed: &[u8]) -> Result<Vec<u8>, Errors> {
+    let mut out = Vec::new();
+    kem::open(&recipient.secret, sealed, config(), &mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn every_recipient_can_open() {
+    let recipients: Vec<KyberModule> = (0..3).map(|_| KyberModule::new().unwrap()).collect();
+    let sealed = seal(&recipients.iter().collect::<Vec<_>>()).unwrap();
+
+    for recipient in &recipients {
+        assert_eq!(open(recipient, &sealed).unwrap(), MESSAGE);
+    }
+
+    let outsider = KyberModule::new().unwrap();
+    assert!(matches!(
+        open(&outsider, &sealed),
+        Err(Errors::KyberError(_))
+    ));
+}
+
+#[test]
+fn rejects_no_or_too_many_recipients() {
+    assert!(matches!(seal(&[]), Err(Errors::KyberError(_))));
+
+    let recipient = KyberModule::new().unwrap();
+    let too_many = vec![&recipient; MAX_RECIPIENTS + 1];
+    assert!(matches!(seal(&too_many), Err(Errors::KyberError(_))));
+}
+
+#[test]
+fn recipient_list_cannot_be_changed() {
+    let (first, second) = (KyberModule::new().unwrap(), KyberModule::new().unwrap());
+    let sealed = seal(&[&first, &second]).unwrap();
+    assert_eq!(open(&second, &sealed).unwrap(), MESSAGE);
+
+    // Cutting the second entry out leaves a well-formed envelope for the first recipient.
+    let ciphertext_len = first.public.encapsulate().unwrap().1.as_bytes().len();
+    let entry_len = |at: usize| {
+        let len = &sealed[at + ciphertext_len..at + ciphertext_len + 2];
+        ciphertext_len + 2 + u16::from_le_bytes([len[0], len[1]]) as usize
+    };
+    let second_entry = 6 + entry_len(6);
+    let mut removed = sealed.clone();
+    removed.drain(second_entry..second_entry + entry_len(second_entry));
+    removed[5] = 1;
+    assert!(matches!(open(&first, &removed), Err(Errors::InvalidMac(_))));
+
+    let mut tampered = sealed.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(open(&first, &tampered).is_err());
+    assert!(open(&second, &tampered).is_err());
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "key_derivation")]
+
+use crystalyst_rs::{
+    Config, Errors,
+    cipher::keyslot::{KeySlotContainer, MAX_SLOTS, SlotKey, SlotKind},
+    profiles,
+};
+
+const OWNER: &[u8] = &[0x42; 32];
+const GUEST: &[u8] = &[0x24; 32];
+const DATA: &[u8] = b"shared between several keys";
+
+fn config() -> Config {
+    profiles::FAST.key_derivation(false)
+}
+
+fn new_container() -> KeySlotContainer {
+    KeySlotContainer::new(config(), DATA, OWNER).unwrap()
+}
+
+/// Round-trips the container through its serialized form before opening it.
+fn open(container: &KeySlotContainer, key: SlotKey<'_>) -> Result<Vec<u8>, Errors> {
+    let container = KeySlotContainer::from_bytes(&container.to_bytes())?.config(config());
+    let mut out = Vec::new();
+    container.decrypt(key, &mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn every_slot_unlocks_the_payload() {
+    let mut container = new_container();
+    assert_eq!(
+        container
+            .add_password(SlotKey::Password(OWNER), GUEST)
+            .unwrap(),
+        1
+    );
+    let (index, recovery_key) = container
+        .add_recovery_key(SlotKey::Password(GUEST))
+        .unwrap();
+    assert_eq!(index, 2);
+    assert_eq!(
+        container.slot_kinds(),
+        [
+            SlotKind::Password,
+            SlotKind::Password,
+            SlotKind::RecoveryKey
+        ]
+    );
+
+    for key in [
+        SlotKey::Password(OWNER),
+        SlotKey::Password(GUEST),
+        SlotKey::RecoveryKey(recovery_key.expose_secret()),
+    ] {
+        assert_eq!(open(&container, key).unwrap(), DATA);
+    }
+    assert!(matches!(
+        open(&container, SlotKey::Password(&[0x11; 32])),
+        Err(Errors::KeySlotError(_))
+    ));
+}
+
+#[test]
+fn changing_slots_needs_an_existing_key() {
+    let mut container = new_container();
+    assert!(
+        container
+            .add_password(SlotKey::Password(GUEST), GUEST)
+            .is_err()
+    );
+    assert!(
+        container
+            .add_recovery_key(SlotKey::Password(GUEST))
+            .is_err()
+    );
+    assert_eq!(container.slot_kinds(), [SlotKind::Password]);
+}
+
+#[test]
+fn revoked_slot_no_longer_unlocks() {
+    let mut container = new_container();
+    container
+        .add_password(SlotKey::Password(OWNER), GUEST)
+        .unwrap();
+
+    assert!(container.revoke(SlotKey::Password(&[0x11; 32]), 1).is_err());
+    assert!(container.revoke(SlotKey::Password(OWNER), 2).is_err());
+    container.revoke(SlotKey::Password(GUEST), 1).unwrap();
+
+    assert!(open(&container, SlotKey::Password(GUEST)).is_err());
+    assert_eq!(open(&container, SlotKey::Password(OWNER)).unwrap(), DATA);
+    assert!(matches!(
+        container.revoke(SlotKey::Password(OWNER), 0),
+        Err(Errors::KeySlotError(_))
+    ));
+}
+
+#[test]
+fn slot_count_is_capped() {
+    let mut container = new_container();
+    for _ in 1..MAX_SLOTS {
+        container
+            .add_password(SlotKey::Password(OWNER), GUEST)
+            .unwrap();
+    }
+    assert!(matches!(
+        container.add_password(SlotKey::Password(OWNER), GUEST),
+        Err(Errors::KeySlotError(_))
+    ));
+}
+
+#[test]
+fn rejects_tampered_container() {
+    let bytes = new_container().to_bytes();
+    for i in [0, 4, 5, 37, 38, bytes.len() - 1] {
+        let mut tampered = bytes.clone();
+        tampered[i] ^= 1;
+        let opened = KeySlotContainer::from_bytes(&tampered).and_then(|container| {
+            container
+                .config(config())
+                .decrypt(SlotKey::Password(OWNER), &mut Vec::new())
+        });
+        assert!(opened.is_err(), "flipped byte {}", i);
+    }
+}
+
+#[cfg(feature = "pqc_kyber")]
+#[test]
+fn kyber_slot_unlocks_for_its_recipient_only() {
+    use crystalyst_rs::utils::kyber::KyberModule;
+
+    let recipient = KyberModule::new().unwrap();
+    let other = KyberModule::new().unwrap();
+    let mut container = new_container();
+    let index = container
+        .add_kyber(SlotKey::Password(OWNER), &recipient.public)
+        .unwrap();
+    assert_eq!(container.slot_kinds()[index], SlotKind::Kyber);
+
+    assert_eq!(open(&container, SlotKey::Kyber(&recipient)).unwrap(), DATA);
+    assert!(open(&container, SlotKey::Kyber(&other)).is_err());
+
+    container.revoke(SlotKey::Kyber(&recipient), index).unwrap();
+    assert!(open(&container, SlotKey::Kyber(&recipient)).is_err());
+}
@@ -0,0 +1,104 @@
+#![cfg(all(feature = "pqc_kyber", feature = "base_coding"))]
+
+use crystalyst_rs::{
+    Errors,
+    utils::kyber::{KyberModule, KyberPublicKey},
+};
+
+/// Lines of an armored key before the base64 body: begin, level and a blank line.
+const BODY_LINE: usize = 3;
+
+/// Index of the checksum line, right before the end line.
+fn checksum_line(armored: &str) -> usize {
+    armored.lines().count() - 2
+}
+
+/// Changes the last character of line `index`, keeping it valid base64.
+fn damage_line(armored: &str, index: usize) -> String {
+    let mut lines: Vec<String> = armored.lines().map(str::to_string).collect();
+    let line = &mut lines[index];
+    let last = if line.ends_with('A') { "B" } else { "A" };
+    line.replace_range(line.len() - 1.., last);
+    lines.join("\n")
+}
+
+#[test]
+fn public_key_round_trips_through_armor() {
+    let public = KyberModule::new().unwrap().public;
+    let armored = public.to_armored();
+
+    let parsed = KyberPublicKey::from_armored(&armored).unwrap();
+    assert_eq!(parsed, public);
+
+    let pasted = format!(
+        "Here is my key:\n\n  {}\nThanks!",
+        armored.replace('\n', "\n  ")
+    );
+    assert_eq!(KyberPublicKey::from_armored(&pasted).unwrap(), public);
+}
+
+#[test]
+fn public_key_armor_detects_damage() {
+    let armored = KyberModule::new().unwrap().public.to_armored();
+
+    let body = damage_line(&armored, BODY_LINE);
+    let checksum = damage_line(&armored, checksum_line(&armored));
+    for damaged in [body, checksum] {
+        assert!(matches!(
+            KyberPublicKey::from_armored(&damaged),
+            Err(Errors::KyberError(_))
+        ));
+    }
+
+    let cut = armored.replace("-----END", "-----CUT");
+    assert!(KyberPublicKey::from_armored(&cut).is_err());
+}
+
+#[cfg(feature = "key_derivation")]
+mod private_key {
+    use crystalyst_rs::{
+        Config, Errors, profiles,
+        utils::kyber::{KyberModule, KyberPrivateKey, MIN_KEY_PASSWORD_LEN},
+    };
+
+    const PASSWORD: &[u8] = &[0x42; MIN_KEY_PASSWORD_LEN];
+
+    fn config() -> Config {
+        profiles::FAST
+    }
+
+    #[test]
+    fn round_trips_through_armor() {
+        let secret = KyberModule::new().unwrap().secret;
+        let armored = secret.to_armored(PASSWORD, config()).unwrap();
+
+        let parsed = KyberPrivateKey::from_armored(&armored, PASSWORD, config()).unwrap();
+        assert_eq!(parsed.expose_secret(), secret.expose_secret());
+        assert!(KyberPrivateKey::from_armored(&armored, &[0x24; 32], config()).is_err());
+
+        let damaged = super::damage_line(&armored, super::checksum_line(&armored));
+        assert!(matches!(
+            KyberPrivateKey::from_armored(&damaged, PASSWORD, config()),
+            Err(Errors::KyberError(_))
+        ));
+    }
+
+    #[test]
+    fn short_password_is_rejected_with_the_requirement() {
+        let secret = KyberModule::new().unwrap().secret;
+        let short = &PASSWORD[..MIN_KEY_PASSWORD_LEN - 1];
+
+        match secret.to_encrypted_bytes(short, config()) {
+            Err(Errors::KyberError(message)) => {
+                assert!(message.contains(&MIN_KEY_PASSWORD_LEN.to_string()))
+            }
+            other => panic!("expected a password length error, got {:?}", other.err()),
+        }
+
+        let encrypted = secret.to_encrypted_bytes(PASSWORD, config()).unwrap();
+        assert!(matches!(
+            KyberPrivateKey::from_encrypted_bytes(&encrypted, short, config()),
+            Err(Errors::KyberError(_))
+        ));
+    }
+}
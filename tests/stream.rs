@@ -0,0 +1,263 @@
+#![cfg(feature = "key_derivation")]
+
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+use crystalyst_rs::{
+    Config, Errors, ThreadStrategy,
+    cipher::stream_cipher::{CrystalystStream, TAG_SIZE},
+    profiles,
+    rng_utils::{
+        nonce::{Nonce, NonceType},
+        rng::RNG,
+    },
+};
+
+const PASSWORD: &[u8] = &[0x42; 32];
+const NEW_PASSWORD: &[u8] = &[0x24; 32];
+const CHUNK: usize = 64;
+const FRAME: usize = CHUNK + TAG_SIZE;
+
+fn config() -> Config {
+    profiles::FAST.key_derivation(false)
+}
+
+fn stream() -> CrystalystStream {
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+    CrystalystStream::new(config(), PASSWORD, nonce).chunk_size(CHUNK)
+}
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+fn encrypt(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    stream().stream_reader_encrypt(data, &mut out).unwrap();
+    out
+}
+
+fn decrypt(encrypted: &[u8]) -> Result<Vec<u8>, Errors> {
+    let mut out = Vec::new();
+    CrystalystStream::open(config(), PASSWORD).stream_reader_decrypt(encrypted, &mut out)?;
+    Ok(out)
+}
+
+/// Length of the stream header in front of the frames of `data_len` plaintext bytes.
+fn header_len(encrypted: &[u8], data_len: usize) -> usize {
+    encrypted.len() - (data_len / CHUNK) * FRAME - (data_len % CHUNK + TAG_SIZE)
+}
+
+#[test]
+fn round_trips_around_chunk_boundaries() {
+    for len in [0, 1, CHUNK - 1, CHUNK, CHUNK + 1, 5 * CHUNK, 5 * CHUNK + 3] {
+        let data = data(len);
+        assert_eq!(decrypt(&encrypt(&data)).unwrap(), data, "length {}", len);
+    }
+}
+
+#[test]
+fn rejects_any_flipped_bit() {
+    let data = data(3 * CHUNK + 10);
+    let encrypted = encrypt(&data);
+
+    for i in 0..encrypted.len() {
+        let mut tampered = encrypted.clone();
+        tampered[i] ^= 0x10;
+        assert!(decrypt(&tampered).is_err(), "flipped byte {}", i);
+    }
+}
+
+#[test]
+fn rejects_swapped_chunks() {
+    let data = data(3 * CHUNK + 10);
+    let mut encrypted = encrypt(&data);
+    let start = header_len(&encrypted, data.len());
+
+    let (first, rest) = encrypted[start..].split_at_mut(FRAME);
+    first.swap_with_slice(&mut rest[..FRAME]);
+    assert!(matches!(decrypt(&encrypted), Err(Errors::InvalidMac(_))));
+}
+
+#[test]
+fn rejects_truncated_and_extended_streams() {
+    let data = data(3 * CHUNK);
+    let encrypted = encrypt(&data);
+    let start = header_len(&encrypted, data.len());
+
+    // Dropping the empty final chunk, a whole chunk, or cutting into one.
+    for len in [
+        encrypted.len() - TAG_SIZE,
+        start + 2 * FRAME,
+        start + FRAME + 10,
+        start,
+    ] {
+        assert!(decrypt(&encrypted[..len]).is_err(), "truncated to {}", len);
+    }
+
+    let mut extended = encrypted.clone();
+    extended.push(0);
+    assert!(matches!(decrypt(&extended), Err(Errors::InvalidMac(_))));
+}
+
+#[test]
+fn equal_chunks_encrypt_differently_at_any_distance() {
+    let data = vec![0u8; 300 * CHUNK];
+    let encrypted = encrypt(&data);
+    let start = header_len(&encrypted, data.len());
+    let chunk = |i: usize| &encrypted[start + i * FRAME..start + i * FRAME + CHUNK];
+
+    assert_ne!(chunk(0), chunk(1));
+    assert_ne!(chunk(0), chunk(256));
+    assert_ne!(chunk(1), chunk(257));
+    assert_eq!(decrypt(&encrypted).unwrap(), data);
+}
+
+/// Returns at most one byte per `read`, like a slow pipe.
+struct OneByteReader<'a>(&'a [u8]);
+
+impl Read for OneByteReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((byte, rest)), Some(out)) => {
+                *out = *byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn round_trips_through_one_byte_reads() {
+    for len in [0, 1, CHUNK, 3 * CHUNK + 5] {
+        let data = data(len);
+        let mut encrypted = Vec::new();
+        stream()
+            .stream_reader_encrypt(OneByteReader(&data), &mut encrypted)
+            .unwrap();
+        assert_eq!(encrypted.len(), encrypt(&data).len(), "length {}", len);
+
+        let mut decrypted = Vec::new();
+        CrystalystStream::open(config(), PASSWORD)
+            .stream_reader_decrypt(OneByteReader(&encrypted), &mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, data, "length {}", len);
+    }
+}
+
+#[test]
+fn decrypts_ranges_across_chunk_boundaries() {
+    let data = data(4 * CHUNK + 10);
+    let encrypted = encrypt(&data);
+    let stream = CrystalystStream::open(config(), PASSWORD);
+
+    let ranges = [
+        (0, 1),
+        (0, CHUNK),
+        (CHUNK - 1, 1),
+        (CHUNK - 1, 2),
+        (CHUNK, CHUNK),
+        (CHUNK + 1, 2 * CHUNK),
+        (4 * CHUNK, 10),
+    ];
+    for (offset, len) in ranges {
+        let range = stream
+            .decrypt_range(Cursor::new(&encrypted), offset as u64, len)
+            .unwrap();
+        assert_eq!(range, data[offset..offset + len], "{} at {}", len, offset);
+    }
+
+    // Ranges running past the end are cut short.
+    let tail = stream
+        .decrypt_range(Cursor::new(&encrypted), 4 * CHUNK as u64 + 5, 100)
+        .unwrap();
+    assert_eq!(tail, data[4 * CHUNK + 5..]);
+    let past = stream
+        .decrypt_range(Cursor::new(&encrypted), data.len() as u64 + 1, 10)
+        .unwrap();
+    assert!(past.is_empty());
+}
+
+#[test]
+fn seeks_to_chunk_boundaries() {
+    let data = data(3 * CHUNK + 7);
+    let encrypted = encrypt(&data);
+    let mut reader = CrystalystStream::open(config(), PASSWORD)
+        .seekable_reader(Cursor::new(&encrypted))
+        .unwrap();
+    assert_eq!(reader.len(), data.len() as u64);
+
+    let mut buffer = [0u8; 3];
+    for offset in [CHUNK - 1, 2 * CHUNK, CHUNK + 1, 0, 3 * CHUNK + 4] {
+        reader.seek(SeekFrom::Start(offset as u64)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(buffer, data[offset..offset + 3], "offset {}", offset);
+    }
+
+    reader.seek(SeekFrom::End(-2)).unwrap();
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, data[data.len() - 2..]);
+    assert!(
+        reader
+            .seek(SeekFrom::Current(-(data.len() as i64) - 1))
+            .is_err()
+    );
+}
+
+#[test]
+fn round_trips_with_any_worker_count() {
+    let data = data(20 * CHUNK + 9);
+    for threads in [1, 3, 8] {
+        for in_flight in [1, 2, 16] {
+            let config = config().set_thread(ThreadStrategy::Custom(threads));
+            let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+            let mut encrypted = Vec::new();
+            CrystalystStream::new(config, PASSWORD, nonce)
+                .chunk_size(CHUNK)
+                .chunks_in_flight(in_flight)
+                .stream_reader_encrypt(&data[..], &mut encrypted)
+                .unwrap();
+
+            let mut decrypted = Vec::new();
+            CrystalystStream::open(config, PASSWORD)
+                .chunks_in_flight(in_flight)
+                .stream_reader_decrypt(&encrypted[..], &mut decrypted)
+                .unwrap();
+            assert_eq!(
+                decrypted, data,
+                "{} threads, {} in flight",
+                threads, in_flight
+            );
+        }
+    }
+}
+
+#[test]
+fn rekey_moves_stream_to_the_new_password() {
+    let data = data(3 * CHUNK + 10);
+    let encrypted = encrypt(&data);
+    let rekey = |encrypted: &[u8]| {
+        let mut out = Vec::new();
+        CrystalystStream::open(config(), PASSWORD)
+            .stream_reader_rekey(NEW_PASSWORD, encrypted, &mut out)
+            .map(|_| out)
+    };
+
+    let rekeyed = rekey(&encrypted).unwrap();
+    assert_eq!(rekeyed.len(), encrypted.len());
+    assert!(decrypt(&rekeyed).is_err());
+
+    let mut decrypted = Vec::new();
+    CrystalystStream::open(config(), NEW_PASSWORD)
+        .stream_reader_decrypt(&rekeyed[..], &mut decrypted)
+        .unwrap();
+    assert_eq!(decrypted, data);
+
+    // The old password no longer opens it, and tampered input is not re-encrypted.
+    assert!(rekey(&rekeyed).is_err());
+    let mut tampered = encrypted.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(matches!(rekey(&tampered), Err(Errors::InvalidMac(_))));
+}
@@ -0,0 +1,77 @@
+//! Throughput of the fused engine against the layer-by-layer reference pipeline.
+//!
+//! Takes minutes and several GiB of memory, run it with
+//! `cargo test --release --test throughput -- --ignored --nocapture`.
+//! `CRYSTALYST_BENCH_MIB` sets the data size, 1024 MiB by default.
+
+#![cfg(feature = "key_derivation")]
+
+use std::time::{Duration, Instant};
+
+use crystalyst_rs::{
+    Config, ThreadStrategy,
+    cipher::{block_cipher::CrystalystBuilder, reference::reference_encrypt},
+    profiles,
+    rng_utils::{
+        nonce::{Nonce, NonceType},
+        rng::RNG,
+    },
+};
+
+const PASSWORD: &[u8] = &[0x42; 32];
+
+fn data_len() -> usize {
+    let mib = std::env::var("CRYSTALYST_BENCH_MIB")
+        .ok()
+        .and_then(|mib| mib.parse().ok())
+        .unwrap_or(1024);
+    mib * 1024 * 1024
+}
+
+fn report(name: &str, len: usize, elapsed: Duration) {
+    let mb_per_s = len as f64 / 1_000_000.0 / elapsed.as_secs_f64();
+    println!("{:<28} {:>8.2?} {:>8.1} MB/s", name, elapsed, mb_per_s);
+}
+
+#[test]
+#[ignore]
+fn fused_engine_against_layer_by_layer() {
+    let data: Vec<u8> = (0..data_len()).map(|i| (i * 31 + 7) as u8).collect();
+    let nonce = Nonce::generate_nonce(Some(RNG::osrng()), NonceType::Classic).unwrap();
+    let config = profiles::FAST.key_derivation(false);
+    let mut out = Vec::with_capacity(data.len() + 1024);
+
+    let fused = |config: Config, out: &mut Vec<u8>| {
+        let start = Instant::now();
+        CrystalystBuilder::new()
+            .data(&data)
+            .password(PASSWORD)
+            .nonce(nonce)
+            .config(config)
+            .encrypt(out)
+            .unwrap();
+        start.elapsed()
+    };
+
+    println!("{} MiB, {} rounds", data.len() >> 20, config.rounds);
+    let single = fused(config.set_thread(ThreadStrategy::SingleThread), &mut out);
+    report("fused, single thread", data.len(), single);
+    let expected = out.clone();
+    report(
+        "fused, all threads",
+        data.len(),
+        fused(config.set_thread(ThreadStrategy::FullThread), &mut out),
+    );
+    assert_eq!(out, expected);
+
+    let start = Instant::now();
+    reference_encrypt(PASSWORD, &data, nonce, config, &[], &mut out).unwrap();
+    let layered = start.elapsed();
+    report("layer by layer (reference)", data.len(), layered);
+    assert_eq!(out, expected);
+
+    println!(
+        "fused is {:.1}x the layer-by-layer path",
+        layered.as_secs_f64() / single.as_secs_f64()
+    );
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "pqc_kyber")]
+
+use crystalyst_rs::{
+    Errors,
+    utils::{
+        kyber::{KyberModule, KyberPublicKey},
+        trust::{Fingerprint, TrustState, TrustStore},
+    },
+};
+
+fn key() -> KyberPublicKey {
+    KyberModule::new().unwrap().public
+}
+
+fn is_untrusted<T>(result: Result<T, Errors>) -> bool {
+    matches!(result, Err(Errors::UntrustedKey(_)))
+}
+
+#[test]
+fn pins_the_first_key_and_rejects_a_changed_one() {
+    let (alice, mallory) = (key(), key());
+    let mut store = TrustStore::new();
+
+    assert_eq!(store.check("alice", &alice).unwrap(), TrustState::Pinned);
+    assert_eq!(store.check("alice", &alice).unwrap(), TrustState::Pinned);
+    assert!(is_untrusted(store.check("alice", &mallory)));
+    assert_eq!(
+        store.get("alice"),
+        Some((alice.fingerprint(), TrustState::Pinned))
+    );
+
+    // A new key needs an explicit decision.
+    store.trust("alice", &mallory).unwrap();
+    assert_eq!(store.check("alice", &mallory).unwrap(), TrustState::Trusted);
+    assert!(is_untrusted(store.check("alice", &alice)));
+
+    assert!(store.forget("alice"));
+    assert_eq!(store.check("alice", &alice).unwrap(), TrustState::Pinned);
+}
+
+#[test]
+fn without_tofu_only_trusted_names_pass() {
+    let (alice, bob) = (key(), key());
+    let mut store = TrustStore::new().tofu(false);
+    store.trust("alice", &alice).unwrap();
+
+    assert_eq!(store.check("alice", &alice).unwrap(), TrustState::Trusted);
+    assert!(is_untrusted(store.check("bob", &bob)));
+    assert!(store.get("bob").is_none());
+}
+
+#[test]
+fn revoked_key_is_rejected_for_every_name() {
+    let alice = key();
+    let mut store = TrustStore::new();
+    store.trust("alice", &alice).unwrap();
+    store.revoke(alice.fingerprint());
+
+    assert!(store.is_revoked(&alice.fingerprint()));
+    assert!(is_untrusted(store.check("alice", &alice)));
+    assert!(is_untrusted(store.check("alice-laptop", &alice)));
+    assert!(is_untrusted(store.trust("carol", &alice)));
+    assert!(store.get("alice-laptop").is_none());
+}
+
+#[test]
+fn text_format_round_trips() {
+    let (alice, bob, old) = (key(), key(), key());
+    let mut store = TrustStore::new();
+    store.check("alice", &alice).unwrap();
+    store.trust("bob at work", &bob).unwrap();
+    store.revoke(old.fingerprint());
+
+    let mut parsed = TrustStore::from_text(&store.to_text()).unwrap();
+    assert_eq!(parsed.to_text(), store.to_text());
+    assert_eq!(parsed.check("alice", &alice).unwrap(), TrustState::Pinned);
+    assert_eq!(
+        parsed.check("bob at work", &bob).unwrap(),
+        TrustState::Trusted
+    );
+    assert!(parsed.is_revoked(&old.fingerprint()));
+}
+
+#[test]
+fn from_text_names_the_malformed_line() {
+    let fingerprint = key().fingerprint();
+    let lines = [
+        format!("pinned {}", fingerprint),
+        format!("revoked {} alice", fingerprint),
+        format!("trusted {}00 alice", fingerprint),
+        format!("known {} alice", fingerprint),
+        "trusted".to_string(),
+    ];
+
+    for line in lines {
+        let text = format!("# comment\n\ntrusted {} bob\n{}\n", fingerprint, line);
+        match TrustStore::from_text(&text) {
+            Err(Errors::UntrustedKey(message)) => assert!(message.contains("line 4"), "{}", line),
+            other => panic!("{:?} was accepted: {:?}", line, other.map(|s| s.to_text())),
+        }
+    }
+}
+
+#[test]
+fn fingerprint_renders_as_hex_and_words() {
+    let fingerprint = key().fingerprint();
+    let hex = fingerprint.to_hex();
+    assert_eq!(Fingerprint::from_hex(&hex).unwrap(), fingerprint);
+    assert!(Fingerprint::from_hex(&hex[2..]).is_err());
+
+    let words = fingerprint.to_words();
+    assert!(fingerprint.matches_words(&words.to_lowercase().replace('-', " ")));
+    assert!(!key().fingerprint().matches_words(&words));
+}